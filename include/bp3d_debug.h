@@ -0,0 +1,78 @@
+// Copyright (c) 2025, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+#ifndef BP3D_DEBUG_H
+#define BP3D_DEBUG_H
+
+/* C interface to bp3d-debug, available when the host is built with the "ffi" feature.
+ *
+ * All strings are copied before the functions return. Targets, file names, span names and
+ * section names are cached for the lifetime of the process, so they should come from a bounded
+ * set of values. Null strings are replaced by "unknown". */
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define BP3D_DEBUG_LEVEL_TRACE 1
+#define BP3D_DEBUG_LEVEL_DEBUG 2
+#define BP3D_DEBUG_LEVEL_INFO 3
+#define BP3D_DEBUG_LEVEL_WARNING 4
+#define BP3D_DEBUG_LEVEL_ERROR 5
+
+#define BP3D_DEBUG_SECTION_CRITICAL 0
+#define BP3D_DEBUG_SECTION_PERIODIC 1
+#define BP3D_DEBUG_SECTION_EVENT 2
+
+/* Logs a message; msg does not need to be null-terminated. Invalid levels are ignored. */
+void bp3d_debug_log(uint8_t level, const char *target, const char *file, uint32_t line,
+                    const uint8_t *msg, size_t msg_len);
+
+/* Begins a profiler section. Returns 0 if the level is invalid. */
+uint64_t bp3d_debug_section_begin(const char *name, const char *file, uint32_t line,
+                                  uint8_t level);
+
+/* Ends a profiler section. Each handle must be ended exactly once; 0 is ignored. */
+void bp3d_debug_section_end(uint64_t handle);
+
+/* Creates a span, as a child of the span entered on the calling thread if any, and returns its
+ * non-zero identifier. */
+uint64_t bp3d_debug_span_create(const char *name, const char *file, uint32_t line);
+
+void bp3d_debug_span_enter(uint64_t id);
+void bp3d_debug_span_exit(uint64_t id);
+void bp3d_debug_span_destroy(uint64_t id);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
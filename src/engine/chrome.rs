@@ -0,0 +1,397 @@
+// Copyright (c) 2025, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! An engine writing profiler sections and spans in the Chrome trace event format.
+
+use crate::engine::{next_id, owned_fields};
+use crate::field::Field;
+use crate::profiler::section::Section;
+use crate::profiler::Profiler;
+use crate::trace::span::{Callsite, Id};
+use crate::trace::Tracer;
+use crate::util::Location;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::num::NonZeroU32;
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::Mutex;
+
+fn thread_id() -> u64 {
+    static NEXT: AtomicU64 = AtomicU64::new(1);
+    std::thread_local! {
+        static ID: u64 = NEXT.fetch_add(1, Ordering::Relaxed);
+    }
+    ID.with(|v| *v)
+}
+
+fn escape(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[derive(Clone, Copy)]
+struct Site {
+    name: &'static str,
+    location: &'static Location,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Complete(u64),
+    Begin,
+    End,
+}
+
+struct Event {
+    site: Site,
+    phase: Phase,
+    ts: u64,
+    tid: u64,
+    args: Vec<(String, String)>,
+}
+
+impl Event {
+    fn write(&self, out: &mut String) {
+        let (ph, dur) = match self.phase {
+            Phase::Complete(dur) => ("X", Some(dur)),
+            Phase::Begin => ("B", None),
+            Phase::End => ("E", None),
+        };
+        out.push_str("{\"name\":");
+        escape(out, self.site.name);
+        out.push_str(",\"cat\":");
+        escape(out, self.site.location.module_path());
+        let _ = write!(
+            out,
+            ",\"ph\":\"{}\",\"ts\":{}.{:03},\"pid\":{},\"tid\":{}",
+            ph,
+            self.ts / 1000,
+            self.ts % 1000,
+            std::process::id(),
+            self.tid
+        );
+        if let Some(dur) = dur {
+            let _ = write!(out, ",\"dur\":{}.{:03}", dur / 1000, dur % 1000);
+        }
+        if self.phase != Phase::End {
+            out.push_str(",\"args\":{\"location\":");
+            escape(
+                out,
+                &format!(
+                    "{}:{}",
+                    self.site.location.file(),
+                    self.site.location.line()
+                ),
+            );
+            for (name, value) in &self.args {
+                out.push(',');
+                escape(out, name);
+                out.push(':');
+                escape(out, value);
+            }
+            out.push('}');
+        }
+        out.push('}');
+    }
+}
+
+struct OpenSpan {
+    site: Site,
+    args: Vec<(String, String)>,
+}
+
+/// An engine which buffers profiler sections and spans and writes them as a Chrome trace event
+/// JSON file, which can be opened in Perfetto or chrome://tracing.
+///
+/// Sections are written as complete events and each enter/exit pair of a span as a begin/end
+/// pair on the thread which entered it. Log messages are discarded.
+#[derive(Default)]
+pub struct ChromeTraceWriter {
+    sites: Mutex<Vec<Site>>,
+    spans: Mutex<HashMap<Id, OpenSpan>>,
+    instance: AtomicU32,
+    events: Mutex<Vec<Event>>,
+}
+
+impl ChromeTraceWriter {
+    /// Creates a new empty Chrome trace writer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn register(&self, site: Site) -> NonZeroU32 {
+        let mut sites = self.sites.lock().unwrap();
+        sites.push(site);
+        unsafe { NonZeroU32::new_unchecked(sites.len() as _) }
+    }
+
+    fn span_event(&self, id: Id, phase: Phase) {
+        let spans = self.spans.lock().unwrap();
+        if let Some(span) = spans.get(&id) {
+            let event = Event {
+                site: span.site,
+                phase,
+                ts: crate::util::clock().monotonic_ns(),
+                tid: thread_id(),
+                args: match phase {
+                    Phase::End => Vec::new(),
+                    _ => span.args.clone(),
+                },
+            };
+            self.events.lock().unwrap().push(event);
+        }
+    }
+
+    /// Writes all events recorded so far as a JSON array.
+    ///
+    /// Spans which were entered but never exited are closed at the time of the last recorded
+    /// event, so that every begin event has a matching end event.
+    pub fn write(&self, out: &mut impl Write) -> std::io::Result<()> {
+        let events = self.events.lock().unwrap();
+        let last = events.iter().map(|v| v.ts).max().unwrap_or(0);
+        let mut open: HashMap<u64, Vec<Site>> = HashMap::new();
+        let mut buf = String::from("[");
+        for (i, event) in events.iter().enumerate() {
+            match event.phase {
+                Phase::Begin => open.entry(event.tid).or_default().push(event.site),
+                Phase::End => {
+                    open.entry(event.tid).or_default().pop();
+                }
+                Phase::Complete(_) => (),
+            }
+            if i > 0 {
+                buf.push(',');
+            }
+            buf.push('\n');
+            event.write(&mut buf);
+        }
+        for (tid, sites) in open {
+            for site in sites.into_iter().rev() {
+                buf.push_str(",\n");
+                Event {
+                    site,
+                    phase: Phase::End,
+                    ts: last,
+                    tid,
+                    args: Vec::new(),
+                }
+                .write(&mut buf);
+            }
+        }
+        buf.push_str("\n]\n");
+        out.write_all(buf.as_bytes())
+    }
+
+    /// Writes all events recorded so far to the file at the given path.
+    pub fn finish(&self, path: &Path) -> std::io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        self.write(&mut file)?;
+        file.flush()
+    }
+}
+
+impl Profiler for ChromeTraceWriter {
+    fn section_register(&self, section: &'static Section) -> NonZeroU32 {
+        self.register(Site {
+            name: section.name(),
+            location: section.location(),
+        })
+    }
+
+    fn section_record(&self, id: NonZeroU32, start: u64, end: u64, fields: &[Field]) {
+        // Ignore ids which were not handed out by this writer.
+        let Some(site) = self
+            .sites
+            .lock()
+            .unwrap()
+            .get(id.get() as usize - 1)
+            .copied()
+        else {
+            return;
+        };
+        let event = Event {
+            site,
+            phase: Phase::Complete(end.saturating_sub(start)),
+            ts: start,
+            tid: thread_id(),
+            args: owned_fields(fields),
+        };
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+impl Tracer for ChromeTraceWriter {
+    fn register_callsite(&self, callsite: &'static Callsite) -> NonZeroU32 {
+        self.register(Site {
+            name: callsite.name(),
+            location: callsite.location(),
+        })
+    }
+
+    fn span_create(&self, callsite: NonZeroU32, fields: &[Field]) -> NonZeroU32 {
+        let instance = next_id(&self.instance);
+        let Some(site) = self
+            .sites
+            .lock()
+            .unwrap()
+            .get(callsite.get() as usize - 1)
+            .copied()
+        else {
+            return instance;
+        };
+        let span = OpenSpan {
+            site,
+            args: owned_fields(fields),
+        };
+        self.spans
+            .lock()
+            .unwrap()
+            .insert(Id::new(callsite, instance), span);
+        instance
+    }
+
+    fn span_enter(&self, id: Id) {
+        self.span_event(id, Phase::Begin);
+    }
+
+    fn span_record(&self, id: Id, fields: &[Field]) {
+        if let Some(span) = self.spans.lock().unwrap().get_mut(&id) {
+            span.args.extend(owned_fields(fields));
+        }
+    }
+
+    fn span_exit(&self, id: Id) {
+        self.span_event(id, Phase::End);
+    }
+
+    fn span_destroy(&self, id: Id) {
+        self.spans.lock().unwrap().remove(&id);
+    }
+}
+
+crate::engine::discard!(Logger for ChromeTraceWriter);
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::chrome::ChromeTraceWriter;
+    use crate::profiler::section::Level;
+    use crate::{profiler_section, span};
+    use serde_json::Value;
+    use std::collections::HashMap;
+
+    #[test]
+    fn unknown_ids() {
+        use crate::profiler::Profiler;
+        use crate::trace::Tracer;
+        use std::num::NonZeroU32;
+
+        let writer = ChromeTraceWriter::default();
+        writer.section_record(NonZeroU32::MAX, 0, 1, &[]);
+        let instance = writer.span_create(NonZeroU32::MAX, &[]);
+        let id = crate::trace::span::Id::new(NonZeroU32::MAX, instance);
+        writer.span_enter(id);
+        writer.span_exit(id);
+        let mut out = Vec::new();
+        writer.write(&mut out).unwrap();
+        assert_eq!(out, b"[\n]\n");
+    }
+
+    #[test]
+    fn write() {
+        let engine: &'static ChromeTraceWriter = Box::leak(Box::default());
+        crate::engine::with_scoped(engine, || {
+            let value = "a \"quoted\"\nvalue";
+            let span = span!(CHROME, { value });
+            {
+                let _entered = span.enter();
+                drop(profiler_section!("section", Level::Event, { count = 42 }));
+            }
+            std::thread::scope(|s| {
+                s.spawn(|| {
+                    crate::engine::with_scoped(engine, || {
+                        let span = span!(WORKER);
+                        // Never exited: the writer must close it.
+                        std::mem::forget(span.enter());
+                    });
+                });
+            });
+        });
+        let mut out = Vec::new();
+        engine.write(&mut out).unwrap();
+        let events: Vec<Value> = serde_json::from_slice(&out).unwrap();
+        let phases: Vec<(&str, &str)> = events
+            .iter()
+            .map(|v| (v["name"].as_str().unwrap(), v["ph"].as_str().unwrap()))
+            .collect();
+        assert_eq!(
+            phases,
+            [
+                ("CHROME", "B"),
+                ("section", "X"),
+                ("CHROME", "E"),
+                ("WORKER", "B"),
+                ("WORKER", "E")
+            ]
+        );
+        assert_eq!(events[0]["args"]["value"], "a \"quoted\"\nvalue");
+        assert_eq!(events[1]["args"]["count"], "42");
+        assert!(events[1]["dur"].as_f64().unwrap() >= 0.0);
+        assert!(events[1]["args"]["location"]
+            .as_str()
+            .unwrap()
+            .starts_with("src/engine/chrome.rs:"));
+        assert_ne!(events[0]["tid"], events[3]["tid"]);
+        let mut depth: HashMap<u64, i32> = HashMap::new();
+        for event in &events {
+            let tid = event["tid"].as_u64().unwrap();
+            match event["ph"].as_str().unwrap() {
+                "B" => *depth.entry(tid).or_default() += 1,
+                "E" => *depth.entry(tid).or_default() -= 1,
+                _ => (),
+            }
+            assert!(depth.get(&tid).copied().unwrap_or(0) >= 0);
+        }
+        assert!(depth.values().all(|v| *v == 0));
+    }
+}
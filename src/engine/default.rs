@@ -29,12 +29,25 @@
 use crate::engine::ENGINE_INIT_FLAG;
 use crate::field::Field;
 use crate::trace::span::{Callsite, Id};
-use std::fmt::Arguments;
-use std::num::NonZeroU32;
-use std::sync::atomic::Ordering;
+use core::fmt::Arguments;
+use core::num::NonZeroU32;
+use core::sync::atomic::Ordering;
 
 pub struct DefaultDebugger {}
 
+#[cfg(feature = "std")]
+struct Fields<'a>(&'a [Field<'a>]);
+
+#[cfg(feature = "std")]
+impl core::fmt::Display for Fields<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for field in self.0 {
+            write!(f, ", {}={}", field.name(), field.value())?;
+        }
+        Ok(())
+    }
+}
+
 impl crate::profiler::Profiler for DefaultDebugger {
     fn section_register(&self, _: &'static crate::profiler::section::Section) -> NonZeroU32 {
         ENGINE_INIT_FLAG.store(true, Ordering::Relaxed);
@@ -75,18 +88,20 @@ impl crate::trace::Tracer for DefaultDebugger {
 }
 
 impl crate::logger::Logger for DefaultDebugger {
+    #[cfg(feature = "std")]
     fn log(&self, callsite: &'static crate::logger::Callsite, args: Arguments, fields: &[Field]) {
-        let mut s = String::new();
-        for field in fields {
-            s += &format!(", {}={}", field.name(), field.value());
-        }
         println!(
             "[{}] {}: {}{}",
             callsite.level(),
             callsite.location().module_path(),
             args,
-            s
+            Fields(fields)
         );
         ENGINE_INIT_FLAG.store(true, Ordering::Relaxed);
     }
+
+    #[cfg(not(feature = "std"))]
+    fn log(&self, _: &'static crate::logger::Callsite, _: Arguments, _: &[Field]) {
+        ENGINE_INIT_FLAG.store(true, Ordering::Relaxed);
+    }
 }
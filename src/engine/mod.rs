@@ -26,9 +26,19 @@
 // NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::sync::atomic::{AtomicBool, Ordering};
+use core::sync::atomic::{AtomicBool, Ordering};
 
+#[cfg(feature = "std")]
+pub mod chrome;
 mod default;
+#[cfg(feature = "otlp")]
+pub mod otlp;
+#[cfg(feature = "std")]
+pub mod profiling;
+#[cfg(all(feature = "std", any(test, feature = "testing")))]
+pub mod recording;
+#[cfg(feature = "std")]
+pub mod text;
 
 pub trait Engine:
     crate::logger::Logger + crate::profiler::Profiler + crate::trace::Tracer + Sync
@@ -39,11 +49,84 @@ impl<T: crate::logger::Logger + crate::profiler::Profiler + crate::trace::Tracer
 {
 }
 
+/// Implements [Logger](crate::logger::Logger) or [Tracer](crate::trace::Tracer) for an engine
+/// which discards log messages or spans.
+#[cfg(feature = "std")]
+macro_rules! discard {
+    (Logger for $t: ty) => {
+        impl $crate::logger::Logger for $t {
+            fn enabled(&self, _: &'static $crate::logger::Callsite) -> bool {
+                false
+            }
+
+            fn log(
+                &self,
+                _: &'static $crate::logger::Callsite,
+                _: core::fmt::Arguments,
+                _: &[$crate::field::Field],
+            ) {
+            }
+        }
+    };
+    (Tracer for $t: ty) => {
+        impl $crate::trace::Tracer for $t {
+            fn register_callsite(
+                &self,
+                _: &'static $crate::trace::span::Callsite,
+            ) -> core::num::NonZeroU32 {
+                core::num::NonZeroU32::MIN
+            }
+
+            fn span_create(
+                &self,
+                _: core::num::NonZeroU32,
+                _: &[$crate::field::Field],
+            ) -> core::num::NonZeroU32 {
+                core::num::NonZeroU32::MIN
+            }
+
+            fn span_enter(&self, _: $crate::trace::span::Id) {}
+
+            fn span_record(&self, _: $crate::trace::span::Id, _: &[$crate::field::Field]) {}
+
+            fn span_exit(&self, _: $crate::trace::span::Id) {}
+
+            fn span_destroy(&self, _: $crate::trace::span::Id) {}
+        }
+    };
+}
+
+#[cfg(feature = "std")]
+pub(crate) use discard;
+
+/// Formats fields as owned name and value pairs, for engines which keep them past the call.
+#[cfg(feature = "std")]
+pub(crate) fn owned_fields(fields: &[crate::field::Field]) -> Vec<(String, String)> {
+    fields
+        .iter()
+        .map(|v| (v.name().into(), v.value().to_string()))
+        .collect()
+}
+
+/// Returns the next value of an id counter, skipping 0 when the counter wraps around.
+#[cfg(feature = "std")]
+pub(crate) fn next_id(counter: &core::sync::atomic::AtomicU32) -> core::num::NonZeroU32 {
+    loop {
+        if let Some(v) = core::num::NonZeroU32::new(counter.fetch_add(1, Ordering::Relaxed)) {
+            break v;
+        }
+    }
+}
+
 static ENGINE_INIT_FLAG: AtomicBool = AtomicBool::new(false);
 
 static mut ENGINE: &dyn Engine = &default::DefaultDebugger {};
 
 pub fn get() -> &'static dyn Engine {
+    #[cfg(all(feature = "std", any(test, feature = "testing")))]
+    if let Some(engine) = scoped() {
+        return engine;
+    }
     unsafe { ENGINE }
 }
 
@@ -54,13 +137,94 @@ pub fn set(engine: &'static dyn Engine) -> bool {
     }
     unsafe { ENGINE = engine };
     ENGINE_INIT_FLAG.store(true, Ordering::Relaxed);
+    crate::logger::rebuild_interest();
     true
 }
 
+/// An engine installed by [with_scoped] with the ids it assigned to sections and span callsites.
+#[cfg(all(feature = "std", any(test, feature = "testing")))]
+struct Scope {
+    engine: &'static dyn Engine,
+    // Keyed by the address of the section or span callsite.
+    ids: std::collections::HashMap<usize, core::num::NonZeroU32>,
+}
+
+#[cfg(all(feature = "std", any(test, feature = "testing")))]
+std::thread_local! {
+    static SCOPES: core::cell::RefCell<Vec<Scope>> = const { core::cell::RefCell::new(Vec::new()) };
+}
+
+/// Returns the engine installed by [with_scoped] on the current thread, if any.
+#[cfg(all(feature = "std", any(test, feature = "testing")))]
+pub(crate) fn scoped() -> Option<&'static dyn Engine> {
+    // During thread-local destruction the global engine is used.
+    SCOPES
+        .try_with(|v| v.borrow().last().map(|v| v.engine))
+        .ok()
+        .flatten()
+}
+
+/// Returns the id assigned to `key` by the engine installed by [with_scoped] on the current
+/// thread, calling `register` the first time the key is seen in this scope.
+#[cfg(all(feature = "std", any(test, feature = "testing")))]
+pub(crate) fn scoped_id(
+    key: usize,
+    register: impl FnOnce(&dyn Engine) -> core::num::NonZeroU32,
+) -> Option<core::num::NonZeroU32> {
+    let found = SCOPES
+        .try_with(|v| {
+            let scopes = v.borrow();
+            let scope = scopes.last()?;
+            Some(scope.ids.get(&key).copied().ok_or(scope.engine))
+        })
+        .ok()
+        .flatten()?;
+    let engine = match found {
+        Ok(id) => return Some(id),
+        Err(engine) => engine,
+    };
+    // The engine may use instrumentation while registering, so the stack must not be borrowed.
+    let id = register(engine);
+    let _ = SCOPES.try_with(|v| {
+        if let Some(scope) = v.borrow_mut().last_mut() {
+            scope.ids.insert(key, id);
+        }
+    });
+    Some(id)
+}
+
+/// Runs the given closure with `engine` overriding the global engine on the current thread.
+///
+/// Scopes can be nested; the previous engine is restored when the closure returns or unwinds.
+/// Span callsites and profiler sections are registered with the scoped engine on first use in
+/// each scope instead of using the ids cached by the global engine.
+///
+/// Spans and section guards created in the scope keep the ids assigned by the scoped engine, so
+/// they should not outlive the closure; engines ignore ids they did not hand out.
+#[cfg(all(feature = "std", any(test, feature = "testing")))]
+pub fn with_scoped<R>(engine: &'static dyn Engine, f: impl FnOnce() -> R) -> R {
+    struct Restore;
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SCOPES.with(|v| v.borrow_mut().pop());
+        }
+    }
+
+    SCOPES.with(|v| {
+        v.borrow_mut().push(Scope {
+            engine,
+            ids: Default::default(),
+        })
+    });
+    let _restore = Restore;
+    f()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::trace::span::Id;
-    use std::num::NonZeroU32;
+    use core::num::NonZeroU32;
 
     #[test]
     fn basic() {
@@ -79,4 +243,89 @@ mod tests {
             &crate::engine::default::DefaultDebugger {}
         ));
     }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn scoped() {
+        use crate::field::Field;
+        use crate::logger::{Callsite as LogCallsite, Logger};
+        use crate::profiler::section::{Level, Section};
+        use crate::profiler::Profiler;
+        use crate::trace::span::Callsite;
+        use crate::trace::Tracer;
+        use crate::{info, profiler_section, span};
+        use core::fmt::Arguments;
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        #[derive(Default)]
+        struct Counter {
+            logs: AtomicUsize,
+            registered: AtomicUsize,
+            sections: AtomicUsize,
+            spans: AtomicUsize,
+        }
+
+        impl Profiler for Counter {
+            fn section_register(&self, _: &'static Section) -> NonZeroU32 {
+                self.registered.fetch_add(1, Ordering::Relaxed);
+                NonZeroU32::MIN
+            }
+
+            fn section_record(&self, _: NonZeroU32, _: u64, _: u64, _: &[Field]) {
+                self.sections.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        impl Tracer for Counter {
+            fn register_callsite(&self, _: &'static Callsite) -> NonZeroU32 {
+                self.registered.fetch_add(1, Ordering::Relaxed);
+                NonZeroU32::MIN
+            }
+
+            fn span_create(&self, _: NonZeroU32, _: &[Field]) -> NonZeroU32 {
+                self.spans.fetch_add(1, Ordering::Relaxed);
+                NonZeroU32::MIN
+            }
+
+            fn span_enter(&self, _: Id) {}
+
+            fn span_record(&self, _: Id, _: &[Field]) {}
+
+            fn span_exit(&self, _: Id) {}
+
+            fn span_destroy(&self, _: Id) {}
+        }
+
+        impl Logger for Counter {
+            fn log(&self, _: &'static LogCallsite, _: Arguments, _: &[Field]) {
+                self.logs.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let outer: &'static Counter = Box::leak(Box::default());
+        let inner: &'static Counter = Box::leak(Box::default());
+        crate::engine::with_scoped(outer, || {
+            info!("outer");
+            crate::engine::with_scoped(inner, || {
+                info!("inner");
+                for _ in 0..2 {
+                    let _span = span!(SCOPED);
+                    drop(profiler_section!("scoped", Level::Event));
+                }
+            });
+            let result = std::panic::catch_unwind(|| {
+                crate::engine::with_scoped(inner, || panic!("unwind"));
+            });
+            assert!(result.is_err());
+            info!("outer");
+        });
+        assert!(crate::engine::scoped().is_none());
+        assert_eq!(outer.logs.load(Ordering::Relaxed), 2);
+        assert_eq!(outer.spans.load(Ordering::Relaxed), 0);
+        assert_eq!(inner.logs.load(Ordering::Relaxed), 1);
+        assert_eq!(inner.spans.load(Ordering::Relaxed), 2);
+        assert_eq!(inner.sections.load(Ordering::Relaxed), 2);
+        // Ids are cached for the duration of the scope.
+        assert_eq!(inner.registered.load(Ordering::Relaxed), 2);
+    }
 }
@@ -0,0 +1,278 @@
+// Copyright (c) 2025, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use crate::engine::otlp::proto::{encode_logs, encode_traces, Attribute, LogData, SpanData, Value};
+use std::io::{BufRead, BufReader, Error, ErrorKind, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, SyncSender};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The configuration of an [OtlpEngine](super::OtlpEngine).
+#[derive(Debug, Clone)]
+pub struct OtlpConfig {
+    pub(crate) endpoint: String,
+    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) service_name: String,
+    pub(crate) batch_size: usize,
+    pub(crate) queue_size: usize,
+    pub(crate) max_open_spans: usize,
+    pub(crate) flush_interval: Duration,
+    pub(crate) timeout: Duration,
+}
+
+impl OtlpConfig {
+    /// Creates a new OTLP configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint`: the base URL of the OTLP/HTTP collector (ex: http://localhost:4318). Only
+    ///   plain HTTP is supported.
+    ///
+    /// returns: OtlpConfig
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            headers: Vec::new(),
+            service_name: "unknown_service".into(),
+            batch_size: 512,
+            queue_size: 2048,
+            max_open_spans: 4096,
+            flush_interval: Duration::from_secs(5),
+            timeout: Duration::from_secs(10),
+        }
+    }
+
+    /// Adds an HTTP header to send with each export request.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sets the `service.name` resource attribute.
+    pub fn service_name(mut self, name: impl Into<String>) -> Self {
+        self.service_name = name.into();
+        self
+    }
+
+    /// Sets the maximum number of spans or log records sent in a single request.
+    pub fn batch_size(mut self, size: usize) -> Self {
+        self.batch_size = size.max(1);
+        self
+    }
+
+    /// Sets the maximum number of spans and log records waiting to be exported. Records
+    /// submitted while the queue is full are dropped.
+    pub fn queue_size(mut self, size: usize) -> Self {
+        self.queue_size = size.max(1);
+        self
+    }
+
+    /// Sets the maximum number of spans created but not yet destroyed. When a span is created
+    /// while this limit is reached, the oldest open span is exported immediately as if it had
+    /// been destroyed.
+    pub fn max_open_spans(mut self, count: usize) -> Self {
+        self.max_open_spans = count.max(1);
+        self
+    }
+
+    /// Sets the maximum amount of time a record waits before being exported.
+    pub fn flush_interval(mut self, interval: Duration) -> Self {
+        self.flush_interval = interval;
+        self
+    }
+
+    /// Sets the connect, read and write timeout of export requests.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+pub enum Command {
+    Span(SpanData),
+    Log(LogData),
+    Flush(SyncSender<()>),
+    Terminate,
+}
+
+struct Endpoint {
+    host: String,
+    base_path: String,
+}
+
+impl Endpoint {
+    fn parse(url: &str) -> std::io::Result<Self> {
+        let url = url.strip_prefix("http://").ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "only http:// endpoints are supported",
+            )
+        })?;
+        let (host, path) = match url.find('/') {
+            Some(pos) => (&url[..pos], url[pos..].trim_end_matches('/')),
+            None => (url, ""),
+        };
+        let host = if host.contains(':') {
+            host.into()
+        } else {
+            format!("{}:80", host)
+        };
+        Ok(Self {
+            host,
+            base_path: path.into(),
+        })
+    }
+}
+
+pub struct Exporter {
+    endpoint: std::io::Result<Endpoint>,
+    headers: Vec<(String, String)>,
+    resource: Vec<Attribute>,
+    batch_size: usize,
+    flush_interval: Duration,
+    timeout: Duration,
+    errors: Arc<AtomicU64>,
+    spans: Vec<SpanData>,
+    logs: Vec<LogData>,
+}
+
+impl Exporter {
+    pub fn new(config: OtlpConfig, errors: Arc<AtomicU64>) -> Self {
+        Self {
+            endpoint: Endpoint::parse(&config.endpoint),
+            headers: config.headers,
+            resource: vec![Attribute::new(
+                "service.name",
+                Value::String(config.service_name),
+            )],
+            batch_size: config.batch_size,
+            flush_interval: config.flush_interval,
+            timeout: config.timeout,
+            errors,
+            spans: Vec::with_capacity(config.batch_size),
+            logs: Vec::with_capacity(config.batch_size),
+        }
+    }
+
+    fn post(&self, path: &str, body: &[u8]) -> std::io::Result<()> {
+        let endpoint = self
+            .endpoint
+            .as_ref()
+            .map_err(|e| Error::new(e.kind(), e.to_string()))?;
+        let addr = endpoint
+            .host
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, "could not resolve endpoint"))?;
+        let mut stream = TcpStream::connect_timeout(&addr, self.timeout)?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+        let mut request = format!(
+            "POST {}{} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/x-protobuf\r\nContent-Length: {}\r\nConnection: close\r\n",
+            endpoint.base_path,
+            path,
+            endpoint.host,
+            body.len()
+        );
+        for (name, value) in &self.headers {
+            request += &format!("{}: {}\r\n", name, value);
+        }
+        request += "\r\n";
+        stream.write_all(request.as_bytes())?;
+        stream.write_all(body)?;
+        stream.flush()?;
+        let mut status = String::new();
+        BufReader::new(stream).read_line(&mut status)?;
+        let code = status.split(' ').nth(1).unwrap_or_default();
+        if code.starts_with('2') {
+            Ok(())
+        } else {
+            Err(Error::other(format!(
+                "collector responded with '{}'",
+                status.trim_end()
+            )))
+        }
+    }
+
+    fn export_spans(&mut self) {
+        if self.spans.is_empty() {
+            return;
+        }
+        let body = encode_traces(&self.resource, &self.spans);
+        self.spans.clear();
+        if self.post("/v1/traces", &body).is_err() {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn export_logs(&mut self) {
+        if self.logs.is_empty() {
+            return;
+        }
+        let body = encode_logs(&self.resource, &self.logs);
+        self.logs.clear();
+        if self.post("/v1/logs", &body).is_err() {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn export_all(&mut self) {
+        self.export_spans();
+        self.export_logs();
+    }
+
+    pub fn run(mut self, queue: Receiver<Command>) {
+        loop {
+            match queue.recv_timeout(self.flush_interval) {
+                Ok(Command::Span(span)) => {
+                    self.spans.push(span);
+                    if self.spans.len() >= self.batch_size {
+                        self.export_spans();
+                    }
+                }
+                Ok(Command::Log(log)) => {
+                    self.logs.push(log);
+                    if self.logs.len() >= self.batch_size {
+                        self.export_logs();
+                    }
+                }
+                Ok(Command::Flush(ack)) => {
+                    self.export_all();
+                    let _ = ack.send(());
+                }
+                Ok(Command::Terminate) | Err(RecvTimeoutError::Disconnected) => {
+                    self.export_all();
+                    break;
+                }
+                Err(RecvTimeoutError::Timeout) => self.export_all(),
+            }
+        }
+    }
+}
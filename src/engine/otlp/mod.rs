@@ -0,0 +1,593 @@
+// Copyright (c) 2025, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! An engine exporting spans and log messages to an OpenTelemetry collector over OTLP/HTTP.
+
+mod export;
+mod proto;
+
+use crate::field::Field;
+use crate::trace::span::{Callsite, Id};
+use crate::util::Location;
+use export::{Command, Exporter};
+use proto::{Attribute, LogData, SpanData, Value};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Arguments;
+use std::num::NonZeroU32;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::mpsc::{sync_channel, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::UNIX_EPOCH;
+
+pub use export::OtlpConfig;
+
+fn now() -> u64 {
+    crate::util::clock()
+        .wall()
+        .duration_since(UNIX_EPOCH)
+        .map(|v| v.as_nanos() as u64)
+        .unwrap_or_default()
+}
+
+fn split_mix(state: u64) -> u64 {
+    let mut z = state.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+fn push_fields(fields: &[Field], out: &mut Vec<Attribute>) {
+    for field in fields {
+        out.push(Attribute::new(field.name(), Value::from(field.value())));
+    }
+}
+
+fn attributes(location: &Location, fields: &[Field]) -> Vec<Attribute> {
+    let mut attrs = Vec::with_capacity(fields.len() + 3);
+    attrs.push(Attribute::new(
+        "code.namespace",
+        Value::String(location.module_path().into()),
+    ));
+    attrs.push(Attribute::new(
+        "code.filepath",
+        Value::String(location.file().into()),
+    ));
+    attrs.push(Attribute::new(
+        "code.lineno",
+        Value::Int(location.line() as _),
+    ));
+    push_fields(fields, &mut attrs);
+    attrs
+}
+
+struct OpenSpan {
+    trace_id: [u8; 16],
+    parent: Option<Id>,
+    name: &'static str,
+    start_time: u64,
+    attributes: Vec<Attribute>,
+}
+
+/// The open spans, indexed by id and by age so that the oldest span is found in O(log n).
+#[derive(Default)]
+struct OpenSpans {
+    spans: HashMap<Id, OpenSpan>,
+    order: BTreeSet<(u64, Id)>,
+}
+
+impl OpenSpans {
+    fn insert(&mut self, id: Id, span: OpenSpan) {
+        self.order.insert((span.start_time, id));
+        if let Some(old) = self.spans.insert(id, span) {
+            self.order.remove(&(old.start_time, id));
+        }
+    }
+
+    fn remove(&mut self, id: &Id) -> Option<OpenSpan> {
+        let span = self.spans.remove(id)?;
+        self.order.remove(&(span.start_time, *id));
+        Some(span)
+    }
+
+    fn pop_oldest(&mut self) -> Option<(Id, OpenSpan)> {
+        let (_, id) = self.order.pop_first()?;
+        self.spans.remove(&id).map(|v| (id, v))
+    }
+}
+
+/// An engine which exports spans and log messages to an OpenTelemetry collector using the
+/// OTLP/HTTP protobuf protocol.
+///
+/// Spans and log records are sent to a background thread through a bounded queue, so
+/// instrumented code never blocks on the network; records submitted while the queue is full are
+/// dropped and accounted in [dropped](OtlpEngine::dropped). Profiler sections are not exported.
+pub struct OtlpEngine {
+    callsites: Mutex<Vec<&'static Callsite>>,
+    spans: Mutex<OpenSpans>,
+    max_open_spans: usize,
+    instance: AtomicU32,
+    trace_seed: u64,
+    trace_counter: AtomicU64,
+    queue: SyncSender<Command>,
+    dropped: AtomicU64,
+    errors: Arc<AtomicU64>,
+    thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl OtlpEngine {
+    /// Creates a new OTLP engine and starts its export thread.
+    pub fn new(config: OtlpConfig) -> Self {
+        let (queue, receiver) = sync_channel(config.queue_size);
+        let max_open_spans = config.max_open_spans;
+        let errors = Arc::new(AtomicU64::new(0));
+        let exporter = Exporter::new(config, errors.clone());
+        let thread = std::thread::Builder::new()
+            .name("bp3d-debug-otlp".into())
+            .spawn(move || exporter.run(receiver))
+            .expect("failed to spawn OTLP export thread");
+        Self {
+            callsites: Mutex::new(Vec::new()),
+            spans: Mutex::new(OpenSpans::default()),
+            max_open_spans,
+            instance: AtomicU32::new(1),
+            trace_seed: now() ^ ((std::process::id() as u64) << 32),
+            trace_counter: AtomicU64::new(0),
+            queue,
+            dropped: AtomicU64::new(0),
+            errors,
+            thread: Mutex::new(Some(thread)),
+        }
+    }
+
+    /// The number of spans and log records dropped because the export queue was full.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// The number of export requests which failed.
+    pub fn export_errors(&self) -> u64 {
+        self.errors.load(Ordering::Relaxed)
+    }
+
+    /// Exports all pending spans and log records and waits for the export to complete.
+    pub fn flush(&self) {
+        let (ack, wait) = sync_channel(1);
+        if self.queue.send(Command::Flush(ack)).is_ok() {
+            let _ = wait.recv();
+        }
+    }
+
+    /// Exports all pending spans and log records and stops the export thread.
+    ///
+    /// Spans and log records submitted after shutdown are dropped.
+    pub fn shutdown(&self) {
+        let thread = self.thread.lock().unwrap().take();
+        if let Some(thread) = thread {
+            let _ = self.queue.send(Command::Terminate);
+            let _ = thread.join();
+        }
+    }
+
+    fn submit(&self, command: Command) {
+        match self.queue.try_send(command) {
+            Ok(()) => (),
+            Err(TrySendError::Full(_)) | Err(TrySendError::Disconnected(_)) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn export_span(&self, id: Id, span: OpenSpan) {
+        self.submit(Command::Span(SpanData {
+            trace_id: span.trace_id,
+            span_id: id.into_raw().get().to_be_bytes(),
+            parent_span_id: span.parent.map(|v| v.into_raw().get().to_be_bytes()),
+            name: span.name,
+            start_time: span.start_time,
+            end_time: now(),
+            attributes: span.attributes,
+        }));
+    }
+
+    fn next_trace_id(&self) -> [u8; 16] {
+        let counter = self.trace_counter.fetch_add(1, Ordering::Relaxed);
+        let high = split_mix(self.trace_seed ^ counter);
+        let low = split_mix(high ^ counter);
+        let mut id = [0; 16];
+        id[..8].copy_from_slice(&high.to_be_bytes());
+        id[8..].copy_from_slice(&low.to_be_bytes());
+        id
+    }
+}
+
+impl Drop for OtlpEngine {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+impl crate::profiler::Profiler for OtlpEngine {
+    fn section_register(&self, _: &'static crate::profiler::section::Section) -> NonZeroU32 {
+        unsafe { NonZeroU32::new_unchecked(1) }
+    }
+
+    fn section_record(&self, _: NonZeroU32, _: u64, _: u64, _: &[Field]) {}
+}
+
+impl crate::trace::Tracer for OtlpEngine {
+    fn register_callsite(&self, callsite: &'static Callsite) -> NonZeroU32 {
+        let mut callsites = self.callsites.lock().unwrap();
+        callsites.push(callsite);
+        unsafe { NonZeroU32::new_unchecked(callsites.len() as _) }
+    }
+
+    fn span_create(&self, callsite: NonZeroU32, fields: &[Field]) -> NonZeroU32 {
+        self.span_create_with_parent(callsite, None, fields)
+    }
+
+    fn span_create_with_parent(
+        &self,
+        callsite: NonZeroU32,
+        parent: Option<Id>,
+        fields: &[Field],
+    ) -> NonZeroU32 {
+        let instance = crate::engine::next_id(&self.instance);
+        // Ignore callsites which were not registered with this engine.
+        let Some(site) = self
+            .callsites
+            .lock()
+            .unwrap()
+            .get(callsite.get() as usize - 1)
+            .copied()
+        else {
+            return instance;
+        };
+        let attrs = attributes(site.location(), fields);
+        let mut spans = self.spans.lock().unwrap();
+        // Children share the trace of their parent; a parent which was already destroyed starts
+        // a new trace but is still recorded as the parent.
+        let trace_id = parent
+            .and_then(|v| spans.spans.get(&v))
+            .map(|v| v.trace_id)
+            .unwrap_or_else(|| self.next_trace_id());
+        let span = OpenSpan {
+            trace_id,
+            parent,
+            name: site.name(),
+            start_time: now(),
+            attributes: attrs,
+        };
+        // Spans which are never destroyed must not grow the map forever: export the oldest one.
+        let mut evicted = None;
+        if spans.spans.len() >= self.max_open_spans {
+            evicted = spans.pop_oldest();
+        }
+        spans.insert(Id::new(callsite, instance), span);
+        drop(spans);
+        if let Some((id, span)) = evicted {
+            self.export_span(id, span);
+        }
+        instance
+    }
+
+    fn span_enter(&self, _: Id) {}
+
+    fn span_record(&self, id: Id, fields: &[Field]) {
+        if let Some(span) = self.spans.lock().unwrap().spans.get_mut(&id) {
+            push_fields(fields, &mut span.attributes);
+        }
+    }
+
+    fn span_exit(&self, _: Id) {}
+
+    fn span_destroy(&self, id: Id) {
+        let span = self.spans.lock().unwrap().remove(&id);
+        if let Some(span) = span {
+            self.export_span(id, span);
+        }
+    }
+}
+
+impl crate::logger::Logger for OtlpEngine {
+    fn log(&self, callsite: &'static crate::logger::Callsite, msg: Arguments, fields: &[Field]) {
+        let attrs = attributes(callsite.location(), fields);
+        self.submit(Command::Log(LogData {
+            time: now(),
+            level: callsite.level(),
+            body: msg.to_string(),
+            attributes: attrs,
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::otlp::{OtlpConfig, OtlpEngine};
+    use crate::logger::{Level, Logger};
+    use crate::trace::span::Callsite;
+    use crate::trace::Tracer;
+    use crate::{callsite, fields, location};
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc::channel;
+    use std::time::Duration;
+
+    #[derive(Debug)]
+    enum Wire {
+        Varint(u64),
+        Fixed64(u64),
+        Bytes(Vec<u8>),
+    }
+
+    fn decode(mut buf: &[u8]) -> Vec<(u32, Wire)> {
+        fn varint(buf: &mut &[u8]) -> u64 {
+            let mut value = 0;
+            let mut shift = 0;
+            loop {
+                let b = buf[0];
+                *buf = &buf[1..];
+                value |= ((b & 0x7F) as u64) << shift;
+                if b & 0x80 == 0 {
+                    return value;
+                }
+                shift += 7;
+            }
+        }
+        let mut fields = Vec::new();
+        while !buf.is_empty() {
+            let tag = varint(&mut buf);
+            let value = match tag & 7 {
+                0 => Wire::Varint(varint(&mut buf)),
+                1 => {
+                    let (v, rest) = buf.split_at(8);
+                    buf = rest;
+                    Wire::Fixed64(u64::from_le_bytes(v.try_into().unwrap()))
+                }
+                2 => {
+                    let len = varint(&mut buf) as usize;
+                    let (v, rest) = buf.split_at(len);
+                    buf = rest;
+                    Wire::Bytes(v.into())
+                }
+                _ => panic!("unexpected wire type"),
+            };
+            fields.push(((tag >> 3) as u32, value));
+        }
+        fields
+    }
+
+    fn message(fields: &[(u32, Wire)], field: u32) -> Vec<(u32, Wire)> {
+        fields
+            .iter()
+            .find_map(|(f, v)| match v {
+                Wire::Bytes(v) if *f == field => Some(decode(v)),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    fn messages(fields: &[(u32, Wire)], field: u32) -> Vec<Vec<(u32, Wire)>> {
+        fields
+            .iter()
+            .filter_map(|(f, v)| match v {
+                Wire::Bytes(v) if *f == field => Some(decode(v)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn string(fields: &[(u32, Wire)], field: u32) -> String {
+        fields
+            .iter()
+            .find_map(|(f, v)| match v {
+                Wire::Bytes(v) if *f == field => Some(String::from_utf8(v.clone()).unwrap()),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    fn scalar(fields: &[(u32, Wire)], field: u32) -> u64 {
+        fields
+            .iter()
+            .find_map(|(f, v)| match v {
+                Wire::Varint(v) | Wire::Fixed64(v) if *f == field => Some(*v),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    fn attribute(attributes: &[Vec<(u32, Wire)>], key: &str) -> Vec<(u32, Wire)> {
+        let kv = attributes.iter().find(|kv| string(kv, 1) == key).unwrap();
+        message(kv, 2)
+    }
+
+    fn mock_collector(requests: usize) -> (String, std::sync::mpsc::Receiver<(String, Vec<u8>)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = channel();
+        std::thread::spawn(move || {
+            for _ in 0..requests {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let path = line.split(' ').nth(1).unwrap().to_string();
+                let mut len = 0;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header == "\r\n" {
+                        break;
+                    }
+                    if let Some(v) = header.strip_prefix("Content-Length: ") {
+                        len = v.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0; len];
+                reader.read_exact(&mut body).unwrap();
+                reader
+                    .get_mut()
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+                    .unwrap();
+                sender.send((path, body)).unwrap();
+            }
+        });
+        (endpoint, receiver)
+    }
+
+    #[test]
+    fn export() {
+        static API_TEST: Callsite = Callsite::new("API_TEST", location!());
+        let (endpoint, requests) = mock_collector(2);
+        let engine = OtlpEngine::new(OtlpConfig::new(endpoint).service_name("otlp-test"));
+        let callsite = engine.register_callsite(&API_TEST);
+        let value = 32;
+        let instance = engine.span_create(callsite, fields!({ value }).as_ref());
+        let id = crate::trace::span::Id::new(callsite, instance);
+        engine.span_record(id, fields!({ test = "test 123" }).as_ref());
+        engine.span_destroy(id);
+        engine.log(
+            callsite!(Level::Warn),
+            format_args!("hello {}", 42),
+            fields!({ flag = true }).as_ref(),
+        );
+        engine.flush();
+
+        let (path, body) = requests.recv().unwrap();
+        assert_eq!(path, "/v1/traces");
+        let resource_spans = message(&decode(&body), 1);
+        let resource = message(&resource_spans, 1);
+        let service = attribute(&messages(&resource, 1), "service.name");
+        assert_eq!(string(&service, 1), "otlp-test");
+        let spans = messages(&message(&resource_spans, 2), 2);
+        assert_eq!(spans.len(), 1);
+        assert_eq!(string(&spans[0], 5), "API_TEST");
+        assert!(scalar(&spans[0], 8) >= scalar(&spans[0], 7));
+        let attributes = messages(&spans[0], 9);
+        assert_eq!(scalar(&attribute(&attributes, "value"), 3), 32);
+        assert_eq!(string(&attribute(&attributes, "test"), 1), "test 123");
+
+        let (path, body) = requests.recv().unwrap();
+        assert_eq!(path, "/v1/logs");
+        let resource_logs = message(&decode(&body), 1);
+        let logs = messages(&message(&resource_logs, 2), 2);
+        assert_eq!(logs.len(), 1);
+        assert_eq!(scalar(&logs[0], 2), 13);
+        assert_eq!(string(&logs[0], 3), "WARNING");
+        assert_eq!(string(&message(&logs[0], 5), 1), "hello 42");
+        let attributes = messages(&logs[0], 6);
+        assert_eq!(scalar(&attribute(&attributes, "flag"), 2), 1);
+        assert_eq!(engine.dropped(), 0);
+        assert_eq!(engine.export_errors(), 0);
+    }
+
+    fn bytes(fields: &[(u32, Wire)], field: u32) -> Option<Vec<u8>> {
+        fields.iter().find_map(|(f, v)| match v {
+            Wire::Bytes(v) if *f == field => Some(v.clone()),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn parent() {
+        static PARENT: Callsite = Callsite::new("PARENT", location!());
+        static CHILD: Callsite = Callsite::new("CHILD", location!());
+        let (endpoint, requests) = mock_collector(1);
+        let engine = OtlpEngine::new(OtlpConfig::new(endpoint));
+        let parent = engine.register_callsite(&PARENT);
+        let parent = crate::trace::span::Id::new(parent, engine.span_create(parent, &[]));
+        let child = engine.register_callsite(&CHILD);
+        let child = crate::trace::span::Id::new(
+            child,
+            engine.span_create_with_parent(child, Some(parent), &[]),
+        );
+        engine.span_destroy(child);
+        engine.span_destroy(parent);
+        engine.flush();
+
+        let (_, body) = requests.recv().unwrap();
+        let spans = messages(&message(&message(&decode(&body), 1), 2), 2);
+        assert_eq!(spans.len(), 2);
+        assert_eq!(string(&spans[0], 5), "CHILD");
+        assert_eq!(string(&spans[1], 5), "PARENT");
+        assert_eq!(bytes(&spans[0], 1), bytes(&spans[1], 1));
+        assert_eq!(bytes(&spans[0], 4), bytes(&spans[1], 2));
+        assert_eq!(bytes(&spans[1], 4), None);
+    }
+
+    #[test]
+    fn max_open_spans() {
+        static OPEN: Callsite = Callsite::new("OPEN", location!());
+        let (endpoint, requests) = mock_collector(1);
+        let engine = OtlpEngine::new(OtlpConfig::new(endpoint).max_open_spans(2));
+        let callsite = engine.register_callsite(&OPEN);
+        let first = engine.span_create(callsite, fields!({ index = 0 }).as_ref());
+        engine.span_create(callsite, fields!({ index = 1 }).as_ref());
+        engine.span_create(callsite, fields!({ index = 2 }).as_ref());
+        let spans = engine.spans.lock().unwrap();
+        assert_eq!(spans.spans.len(), 2);
+        assert_eq!(spans.order.len(), 2);
+        drop(spans);
+        assert!(!engine
+            .spans
+            .lock()
+            .unwrap()
+            .spans
+            .contains_key(&crate::trace::span::Id::new(callsite, first)));
+        engine.flush();
+
+        let (_, body) = requests.recv().unwrap();
+        let spans = messages(&message(&message(&decode(&body), 1), 2), 2);
+        assert_eq!(spans.len(), 1);
+        assert_eq!(scalar(&attribute(&messages(&spans[0], 9), "index"), 3), 0);
+    }
+
+    #[test]
+    fn bounded_queue() {
+        static API_TEST: Callsite = Callsite::new("API_TEST", location!());
+        // The collector accepts connections but never responds, which stalls the export thread.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let engine = OtlpEngine::new(
+            OtlpConfig::new(endpoint)
+                .queue_size(1)
+                .batch_size(1)
+                .timeout(Duration::from_millis(200)),
+        );
+        let callsite = engine.register_callsite(&API_TEST);
+        for _ in 0..64 {
+            let instance = engine.span_create(callsite, &[]);
+            engine.span_destroy(crate::trace::span::Id::new(callsite, instance));
+        }
+        assert!(engine.dropped() >= 62);
+        engine.shutdown();
+        assert!(engine.export_errors() >= 1);
+        drop(listener);
+    }
+}
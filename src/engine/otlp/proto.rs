@@ -0,0 +1,227 @@
+// Copyright (c) 2025, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Minimal protobuf encoder for the subset of the OTLP protocol used by the exporter.
+//!
+//! Field numbers are taken from the opentelemetry-proto definitions (v1).
+
+use crate::field::FieldValue;
+use crate::logger::Level;
+
+const WIRE_VARINT: u32 = 0;
+const WIRE_FIXED64: u32 = 1;
+const WIRE_LEN: u32 = 2;
+
+/// An owned OTLP attribute value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    Bool(bool),
+    Int(i64),
+    Double(f64),
+}
+
+impl From<&FieldValue<'_>> for Value {
+    fn from(value: &FieldValue) -> Self {
+        match value {
+            FieldValue::Int(v) => Value::Int(*v),
+            FieldValue::UInt(v) => match i64::try_from(*v) {
+                Ok(v) => Value::Int(v),
+                Err(_) => Value::String(v.to_string()),
+            },
+            FieldValue::Float(v) => Value::Double(*v as f64),
+            FieldValue::Double(v) => Value::Double(*v),
+            FieldValue::String(v) => Value::String((*v).into()),
+            FieldValue::Debug(v) => Value::String(format!("{:?}", v)),
+            FieldValue::Boolean(v) => Value::Bool(*v),
+        }
+    }
+}
+
+/// An owned OTLP key/value attribute.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attribute {
+    pub key: String,
+    pub value: Value,
+}
+
+impl Attribute {
+    pub fn new(key: impl Into<String>, value: Value) -> Self {
+        Self {
+            key: key.into(),
+            value,
+        }
+    }
+}
+
+/// A completed span ready to be exported.
+#[derive(Debug, Clone)]
+pub struct SpanData {
+    pub trace_id: [u8; 16],
+    pub span_id: [u8; 8],
+    pub parent_span_id: Option<[u8; 8]>,
+    pub name: &'static str,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub attributes: Vec<Attribute>,
+}
+
+/// A log record ready to be exported.
+#[derive(Debug, Clone)]
+pub struct LogData {
+    pub time: u64,
+    pub level: Level,
+    pub body: String,
+    pub attributes: Vec<Attribute>,
+}
+
+/// Maps a [Level] to an OTLP severity number.
+pub fn severity_number(level: Level) -> u64 {
+    match level {
+        Level::Trace => 1,
+        Level::Debug => 5,
+        Level::Info => 9,
+        Level::Warn => 13,
+        Level::Error => 17,
+    }
+}
+
+fn varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn tag(buf: &mut Vec<u8>, field: u32, wire: u32) {
+    varint(buf, ((field << 3) | wire) as u64);
+}
+
+fn varint_field(buf: &mut Vec<u8>, field: u32, value: u64) {
+    tag(buf, field, WIRE_VARINT);
+    varint(buf, value);
+}
+
+fn fixed64_field(buf: &mut Vec<u8>, field: u32, value: u64) {
+    tag(buf, field, WIRE_FIXED64);
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+fn bytes_field(buf: &mut Vec<u8>, field: u32, value: &[u8]) {
+    tag(buf, field, WIRE_LEN);
+    varint(buf, value.len() as u64);
+    buf.extend_from_slice(value);
+}
+
+fn message_field(buf: &mut Vec<u8>, field: u32, f: impl FnOnce(&mut Vec<u8>)) {
+    let mut inner = Vec::new();
+    f(&mut inner);
+    bytes_field(buf, field, &inner);
+}
+
+fn any_value(buf: &mut Vec<u8>, value: &Value) {
+    match value {
+        Value::String(v) => bytes_field(buf, 1, v.as_bytes()),
+        Value::Bool(v) => varint_field(buf, 2, *v as u64),
+        Value::Int(v) => varint_field(buf, 3, *v as u64),
+        Value::Double(v) => fixed64_field(buf, 4, v.to_bits()),
+    }
+}
+
+fn key_value(buf: &mut Vec<u8>, attribute: &Attribute) {
+    bytes_field(buf, 1, attribute.key.as_bytes());
+    message_field(buf, 2, |buf| any_value(buf, &attribute.value));
+}
+
+fn resource(buf: &mut Vec<u8>, attributes: &[Attribute]) {
+    for attribute in attributes {
+        message_field(buf, 1, |buf| key_value(buf, attribute));
+    }
+}
+
+fn scope(buf: &mut Vec<u8>) {
+    bytes_field(buf, 1, env!("CARGO_PKG_NAME").as_bytes());
+    bytes_field(buf, 2, env!("CARGO_PKG_VERSION").as_bytes());
+}
+
+fn span(buf: &mut Vec<u8>, span: &SpanData) {
+    bytes_field(buf, 1, &span.trace_id);
+    bytes_field(buf, 2, &span.span_id);
+    if let Some(parent) = &span.parent_span_id {
+        bytes_field(buf, 4, parent);
+    }
+    bytes_field(buf, 5, span.name.as_bytes());
+    // SPAN_KIND_INTERNAL
+    varint_field(buf, 6, 1);
+    fixed64_field(buf, 7, span.start_time);
+    fixed64_field(buf, 8, span.end_time);
+    for attribute in &span.attributes {
+        message_field(buf, 9, |buf| key_value(buf, attribute));
+    }
+}
+
+fn log_record(buf: &mut Vec<u8>, log: &LogData) {
+    fixed64_field(buf, 1, log.time);
+    varint_field(buf, 2, severity_number(log.level));
+    bytes_field(buf, 3, log.level.as_str().as_bytes());
+    message_field(buf, 5, |buf| bytes_field(buf, 1, log.body.as_bytes()));
+    for attribute in &log.attributes {
+        message_field(buf, 6, |buf| key_value(buf, attribute));
+    }
+}
+
+/// Encodes an `ExportTraceServiceRequest` message.
+pub fn encode_traces(resource_attributes: &[Attribute], spans: &[SpanData]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    message_field(&mut buf, 1, |buf| {
+        message_field(buf, 1, |buf| resource(buf, resource_attributes));
+        message_field(buf, 2, |buf| {
+            message_field(buf, 1, scope);
+            for data in spans {
+                message_field(buf, 2, |buf| span(buf, data));
+            }
+        });
+    });
+    buf
+}
+
+/// Encodes an `ExportLogsServiceRequest` message.
+pub fn encode_logs(resource_attributes: &[Attribute], logs: &[LogData]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    message_field(&mut buf, 1, |buf| {
+        message_field(buf, 1, |buf| resource(buf, resource_attributes));
+        message_field(buf, 2, |buf| {
+            message_field(buf, 1, scope);
+            for data in logs {
+                message_field(buf, 2, |buf| log_record(buf, data));
+            }
+        });
+    });
+    buf
+}
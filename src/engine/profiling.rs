@@ -0,0 +1,282 @@
+// Copyright (c) 2025, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! An engine aggregating profiler sections into per-section statistics.
+
+use crate::field::Field;
+use crate::profiler::section::Section;
+use crate::profiler::table::SectionTable;
+use crate::profiler::Profiler;
+use std::io::Write;
+use std::num::NonZeroU32;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// The number of buckets of the duration histogram of a section.
+///
+/// Bucket `i` counts the calls which lasted less than `2^i` nanoseconds but at least
+/// `2^(i - 1)`, bucket 0 counts the calls of zero nanoseconds and the last bucket holds every
+/// call of at least `2^31` nanoseconds, about 2.1 seconds.
+pub const HISTOGRAM_BUCKETS: usize = 33;
+
+struct Entry {
+    section: &'static Section,
+    count: AtomicU64,
+    total: AtomicU64,
+    min: AtomicU64,
+    max: AtomicU64,
+    histogram: [AtomicU64; HISTOGRAM_BUCKETS],
+}
+
+impl Entry {
+    fn new(section: &'static Section) -> Self {
+        Self {
+            section,
+            count: AtomicU64::new(0),
+            total: AtomicU64::new(0),
+            min: AtomicU64::new(u64::MAX),
+            max: AtomicU64::new(0),
+            histogram: [const { AtomicU64::new(0) }; HISTOGRAM_BUCKETS],
+        }
+    }
+
+    fn stats(&self) -> SectionStats {
+        let count = self.count.load(Ordering::Relaxed);
+        SectionStats {
+            name: self.section.name(),
+            parent: self.section.parent().map(|v| v.name()),
+            count,
+            total: Duration::from_nanos(self.total.load(Ordering::Relaxed)),
+            min: match count {
+                0 => Duration::ZERO,
+                _ => Duration::from_nanos(self.min.load(Ordering::Relaxed)),
+            },
+            max: Duration::from_nanos(self.max.load(Ordering::Relaxed)),
+            histogram: self.histogram.each_ref().map(|v| v.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+/// A snapshot of the statistics of a single section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionStats {
+    /// The name of the section.
+    pub name: &'static str,
+
+    /// The name of the parent section if any.
+    pub parent: Option<&'static str>,
+
+    /// The number of times the section was recorded.
+    pub count: u64,
+
+    /// The total time spent in the section.
+    pub total: Duration,
+
+    /// The shortest recorded duration.
+    pub min: Duration,
+
+    /// The longest recorded duration.
+    pub max: Duration,
+
+    /// The number of calls in each duration bucket, see [HISTOGRAM_BUCKETS].
+    pub histogram: [u64; HISTOGRAM_BUCKETS],
+}
+
+impl SectionStats {
+    /// The average duration of the section.
+    pub fn mean(&self) -> Duration {
+        match self.count {
+            0 => Duration::ZERO,
+            _ => Duration::from_nanos((self.total.as_nanos() / self.count as u128) as u64),
+        }
+    }
+}
+
+/// An engine which aggregates profiler sections into per-section statistics.
+///
+/// Each registered section owns its own set of atomic counters, so recording a section never
+/// locks nor allocates, which keeps [Critical](crate::profiler::section::Level::Critical)
+/// sections cheap. Sections registered past the capacity given to
+/// [new](AggregatingProfiler::new) are ignored. Log messages and spans are discarded.
+pub struct AggregatingProfiler {
+    sections: SectionTable<Entry>,
+}
+
+impl Default for AggregatingProfiler {
+    fn default() -> Self {
+        Self::new(1024)
+    }
+}
+
+impl AggregatingProfiler {
+    /// Creates a new aggregating profiler able to track up to `capacity` sections.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            sections: SectionTable::new(capacity),
+        }
+    }
+
+    /// Returns the statistics of every registered section, sorted by decreasing total time.
+    pub fn report(&self) -> Vec<SectionStats> {
+        let mut stats: Vec<SectionStats> = self.sections.iter().map(Entry::stats).collect();
+        stats.sort_by_key(|v| std::cmp::Reverse(v.total));
+        stats
+    }
+
+    /// Writes the [report](AggregatingProfiler::report) as a human-readable table.
+    pub fn write_report(&self, out: &mut impl Write) -> std::io::Result<()> {
+        writeln!(
+            out,
+            "{:<40} {:>10} {:>14} {:>14} {:>14} {:>14}",
+            "section", "count", "total", "min", "mean", "max"
+        )?;
+        for stats in self.report() {
+            let name = match stats.parent {
+                Some(parent) => format!("{} > {}", parent, stats.name),
+                None => stats.name.into(),
+            };
+            writeln!(
+                out,
+                "{:<40} {:>10} {:>14} {:>14} {:>14} {:>14}",
+                name,
+                stats.count,
+                format!("{:?}", stats.total),
+                format!("{:?}", stats.min),
+                format!("{:?}", stats.mean()),
+                format!("{:?}", stats.max)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Profiler for AggregatingProfiler {
+    fn section_register(&self, section: &'static Section) -> NonZeroU32 {
+        self.sections.register(|| Entry::new(section))
+    }
+
+    fn section_record(&self, id: NonZeroU32, start: u64, end: u64, _: &[Field]) {
+        let Some(entry) = self.sections.get(id) else {
+            return;
+        };
+        let duration = end.saturating_sub(start);
+        let bucket = (u64::BITS - duration.leading_zeros()) as usize;
+        entry.count.fetch_add(1, Ordering::Relaxed);
+        entry.total.fetch_add(duration, Ordering::Relaxed);
+        entry.min.fetch_min(duration, Ordering::Relaxed);
+        entry.max.fetch_max(duration, Ordering::Relaxed);
+        entry.histogram[bucket.min(HISTOGRAM_BUCKETS - 1)].fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+crate::engine::discard!(Tracer for AggregatingProfiler);
+crate::engine::discard!(Logger for AggregatingProfiler);
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::profiling::{AggregatingProfiler, SectionStats, HISTOGRAM_BUCKETS};
+    use crate::location;
+    use crate::profiler::section::{Level, Section};
+    use crate::profiler::Profiler;
+    use std::time::Duration;
+
+    #[test]
+    fn concurrent() {
+        static PARENT: Section = Section::new("parent", location!(), Level::Event);
+        static CHILD: Section =
+            Section::new("child", location!(), Level::Critical).set_parent(&PARENT);
+        let profiler = AggregatingProfiler::default();
+        let parent = profiler.section_register(&PARENT);
+        let child = profiler.section_register(&CHILD);
+        std::thread::scope(|s| {
+            for i in 0..4 {
+                let profiler = &profiler;
+                s.spawn(move || {
+                    for j in 1..=1000 {
+                        profiler.section_record(child, 0, j, &[]);
+                    }
+                    profiler.section_record(parent, 0, 1_000_000 * (i + 1), &[]);
+                });
+            }
+        });
+        let report = profiler.report();
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].name, "parent");
+        assert_eq!(report[0].count, 4);
+        assert_eq!(report[0].total, Duration::from_millis(10));
+        assert_eq!(report[0].min, Duration::from_millis(1));
+        assert_eq!(report[0].max, Duration::from_millis(4));
+        assert_eq!(report[1].name, "child");
+        assert_eq!(report[1].parent, Some("parent"));
+        assert_eq!(report[1].count, 4000);
+        assert_eq!(report[1].total, Duration::from_nanos(4 * 500500));
+        assert_eq!(report[1].min, Duration::from_nanos(1));
+        assert_eq!(report[1].max, Duration::from_nanos(1000));
+        assert_eq!(report[1].mean(), Duration::from_nanos(500));
+        assert_eq!(report[1].histogram.iter().sum::<u64>(), 4000);
+        // 1000ns falls in [512, 1024).
+        assert_eq!(report[1].histogram[10], 4 * 489);
+        assert_eq!(report[1].histogram[HISTOGRAM_BUCKETS - 1], 0);
+
+        let mut out = Vec::new();
+        profiler.write_report(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].starts_with("parent "));
+        assert!(lines[2].starts_with("parent > child "));
+    }
+
+    #[test]
+    fn mean() {
+        let stats = SectionStats {
+            name: "mean",
+            parent: None,
+            count: 1 << 33,
+            total: Duration::from_nanos(3 << 33),
+            min: Duration::ZERO,
+            max: Duration::ZERO,
+            histogram: [0; HISTOGRAM_BUCKETS],
+        };
+        // The count does not fit in the u32 divisor of Duration.
+        assert_eq!(stats.mean(), Duration::from_nanos(3));
+    }
+
+    #[test]
+    fn capacity() {
+        static SECTION: Section = Section::new("section", location!(), Level::Event);
+        let profiler = AggregatingProfiler::new(1);
+        let first = profiler.section_register(&SECTION);
+        let second = profiler.section_register(&SECTION);
+        profiler.section_record(first, 0, 1, &[]);
+        profiler.section_record(second, 0, 1, &[]);
+        let report = profiler.report();
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].count, 1);
+    }
+}
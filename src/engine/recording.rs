@@ -0,0 +1,259 @@
+// Copyright (c) 2025, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! An engine recording everything it receives, for assertions in tests.
+
+use crate::engine::{next_id, owned_fields};
+use crate::field::Field;
+use crate::logger::{Callsite as LogCallsite, Level, Logger};
+use crate::profiler::section::Section;
+use crate::profiler::Profiler;
+use crate::trace::span::{Callsite, Id};
+use crate::trace::Tracer;
+use std::collections::HashMap;
+use std::fmt::Arguments;
+use std::num::NonZeroU32;
+use std::sync::atomic::AtomicU32;
+use std::sync::Mutex;
+
+/// The name and formatted value of each field of an event.
+pub type Fields = Vec<(String, String)>;
+
+/// An event recorded by a [RecordingEngine].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    SpanCreated {
+        id: Id,
+        name: &'static str,
+        parent: Option<Id>,
+        fields: Fields,
+    },
+    SpanEnter(Id),
+    SpanRecord {
+        id: Id,
+        fields: Fields,
+    },
+    SpanExit(Id),
+    SpanDestroy(Id),
+    SectionRecord {
+        name: &'static str,
+        start: u64,
+        end: u64,
+        fields: Fields,
+    },
+    Log {
+        level: Level,
+        module: &'static str,
+        message: String,
+        fields: Fields,
+    },
+}
+
+/// An engine which records all log messages, spans and profiler sections in memory.
+///
+/// This is intended to be installed with [with_scoped](crate::engine::with_scoped) so that tests
+/// can assert on what instrumented code emitted.
+#[derive(Default)]
+pub struct RecordingEngine {
+    ids: AtomicU32,
+    instances: AtomicU32,
+    names: Mutex<HashMap<NonZeroU32, &'static str>>,
+    events: Mutex<Vec<Event>>,
+}
+
+impl RecordingEngine {
+    /// Creates a new empty recording engine.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn register(&self, name: &'static str) -> NonZeroU32 {
+        let id = next_id(&self.ids);
+        self.names.lock().unwrap().insert(id, name);
+        id
+    }
+
+    fn name(&self, id: NonZeroU32) -> &'static str {
+        self.names
+            .lock()
+            .unwrap()
+            .get(&id)
+            .copied()
+            .unwrap_or("unknown")
+    }
+
+    fn push(&self, event: Event) {
+        self.events.lock().unwrap().push(event);
+    }
+
+    /// Returns a copy of all events recorded so far.
+    pub fn events(&self) -> Vec<Event> {
+        self.events.lock().unwrap().clone()
+    }
+
+    /// Returns all events recorded so far and clears the recording.
+    pub fn take_events(&self) -> Vec<Event> {
+        std::mem::take(&mut *self.events.lock().unwrap())
+    }
+
+    /// Returns the ids of all spans created with the given name, in creation order.
+    pub fn spans_named(&self, name: &str) -> Vec<Id> {
+        self.events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|v| match v {
+                Event::SpanCreated { id, name: v, .. } if *v == name => Some(*id),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl Profiler for RecordingEngine {
+    fn section_register(&self, section: &'static Section) -> NonZeroU32 {
+        self.register(section.name())
+    }
+
+    fn section_record(&self, id: NonZeroU32, start: u64, end: u64, fields: &[Field]) {
+        self.push(Event::SectionRecord {
+            name: self.name(id),
+            start,
+            end,
+            fields: owned_fields(fields),
+        });
+    }
+}
+
+impl Tracer for RecordingEngine {
+    fn register_callsite(&self, callsite: &'static Callsite) -> NonZeroU32 {
+        self.register(callsite.name())
+    }
+
+    fn span_create(&self, callsite: NonZeroU32, fields: &[Field]) -> NonZeroU32 {
+        self.span_create_with_parent(callsite, None, fields)
+    }
+
+    fn span_create_with_parent(
+        &self,
+        callsite: NonZeroU32,
+        parent: Option<Id>,
+        fields: &[Field],
+    ) -> NonZeroU32 {
+        let instance = next_id(&self.instances);
+        self.push(Event::SpanCreated {
+            id: Id::new(callsite, instance),
+            name: self.name(callsite),
+            parent,
+            fields: owned_fields(fields),
+        });
+        instance
+    }
+
+    fn span_enter(&self, id: Id) {
+        self.push(Event::SpanEnter(id));
+    }
+
+    fn span_record(&self, id: Id, fields: &[Field]) {
+        self.push(Event::SpanRecord {
+            id,
+            fields: owned_fields(fields),
+        });
+    }
+
+    fn span_exit(&self, id: Id) {
+        self.push(Event::SpanExit(id));
+    }
+
+    fn span_destroy(&self, id: Id) {
+        self.push(Event::SpanDestroy(id));
+    }
+}
+
+impl Logger for RecordingEngine {
+    fn log(&self, callsite: &'static LogCallsite, msg: Arguments, fields: &[Field]) {
+        self.push(Event::Log {
+            level: callsite.level(),
+            module: callsite.location().module_path(),
+            message: msg.to_string(),
+            fields: owned_fields(fields),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::recording::{Event, RecordingEngine};
+    use crate::logger::Level;
+    use crate::profiler::section::Level as SectionLevel;
+    use crate::{info, profiler_section, span};
+
+    #[test]
+    fn record() {
+        let engine: &'static RecordingEngine = Box::leak(Box::default());
+        crate::engine::with_scoped(engine, || {
+            let value = 42;
+            let span = span!(RECORD, { value });
+            {
+                let _entered = span.enter();
+                info!({ value }, "hello {}", "world");
+                drop(profiler_section!("record", SectionLevel::Event));
+            }
+        });
+        let id = engine.spans_named("RECORD")[0];
+        let events = engine.take_events();
+        assert!(engine.events().is_empty());
+        assert_eq!(events.len(), 6);
+        assert_eq!(
+            events[0],
+            Event::SpanCreated {
+                id,
+                name: "RECORD",
+                parent: None,
+                fields: vec![("value".into(), "42".into())]
+            }
+        );
+        assert_eq!(events[1], Event::SpanEnter(id));
+        assert_eq!(
+            events[2],
+            Event::Log {
+                level: Level::Info,
+                module: module_path!(),
+                message: "hello world".into(),
+                fields: vec![("value".into(), "42".into())]
+            }
+        );
+        assert!(matches!(
+            &events[3],
+            Event::SectionRecord { name: "record", start, end, fields }
+                if start <= end && fields.is_empty()
+        ));
+        assert_eq!(events[4], Event::SpanExit(id));
+        assert_eq!(events[5], Event::SpanDestroy(id));
+    }
+}
@@ -0,0 +1,249 @@
+// Copyright (c) 2025, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! An engine which reports spans and profiler sections as log messages.
+
+use crate::field::Field;
+use crate::logger::{Callsite as LogCallsite, Level, Logger};
+use crate::profiler::section::Section;
+use crate::trace::span::{Callsite, Id};
+use crate::util::Location;
+use std::fmt::Arguments;
+use std::num::NonZeroU32;
+use std::sync::atomic::AtomicU32;
+use std::sync::Mutex;
+use std::time::Duration;
+
+static TRACE_CALLSITE: LogCallsite = LogCallsite::new(
+    Location::with_column("bp3d_debug::trace", file!(), line!(), column!()),
+    Level::Trace,
+);
+
+fn register<T>(list: &Mutex<Vec<&'static T>>, item: &'static T) -> NonZeroU32 {
+    let mut list = list.lock().unwrap();
+    list.push(item);
+    unsafe { NonZeroU32::new_unchecked(list.len() as _) }
+}
+
+fn get<T>(list: &Mutex<Vec<&'static T>>, id: NonZeroU32) -> Option<&'static T> {
+    list.lock().unwrap().get(id.get() as usize - 1).copied()
+}
+
+/// An engine which forwards log messages to a [Logger] and reports spans and profiler sections
+/// as trace messages with the target `bp3d_debug::trace`.
+///
+/// This allows reconstructing the timing of an application from its log files alone. Sections
+/// shorter than [min_section_duration](TextTraceEngine::min_section_duration) are not reported to
+/// avoid flooding the logger with hot sections.
+pub struct TextTraceEngine<L> {
+    logger: L,
+    min_section_duration: u64,
+    callsites: Mutex<Vec<&'static Callsite>>,
+    sections: Mutex<Vec<&'static Section>>,
+    instance: AtomicU32,
+}
+
+impl<L: Logger> TextTraceEngine<L> {
+    /// Creates a new text trace engine reporting to the given logger.
+    pub fn new(logger: L) -> Self {
+        Self {
+            logger,
+            min_section_duration: 0,
+            callsites: Mutex::new(Vec::new()),
+            sections: Mutex::new(Vec::new()),
+            instance: AtomicU32::new(1),
+        }
+    }
+
+    /// Sets the minimum duration of a section for it to be reported, by default all sections are
+    /// reported.
+    pub fn min_section_duration(mut self, duration: Duration) -> Self {
+        self.min_section_duration = duration.as_nanos() as _;
+        self
+    }
+
+    fn span_event(&self, event: &str, id: Id, fields: &[Field]) {
+        if !self.logger.enabled(&TRACE_CALLSITE) {
+            return;
+        }
+        let name = get(&self.callsites, id.get_callsite()).map(|v| v.name());
+        self.logger.log(
+            &TRACE_CALLSITE,
+            format_args!(
+                "span {} {} id={:04}-{:04}",
+                event,
+                name.unwrap_or("unknown"),
+                id.get_callsite(),
+                id.get_instance()
+            ),
+            fields,
+        );
+    }
+}
+
+impl<L: Logger> crate::profiler::Profiler for TextTraceEngine<L> {
+    fn section_register(&self, section: &'static Section) -> NonZeroU32 {
+        register(&self.sections, section)
+    }
+
+    fn section_record(&self, id: NonZeroU32, start: u64, end: u64, fields: &[Field]) {
+        let duration = end.saturating_sub(start);
+        if duration < self.min_section_duration || !self.logger.enabled(&TRACE_CALLSITE) {
+            return;
+        }
+        let name = get(&self.sections, id).map(|v| v.name());
+        self.logger.log(
+            &TRACE_CALLSITE,
+            format_args!(
+                "section {} {:?}",
+                name.unwrap_or("unknown"),
+                Duration::from_nanos(duration)
+            ),
+            fields,
+        );
+    }
+}
+
+impl<L: Logger> crate::trace::Tracer for TextTraceEngine<L> {
+    fn register_callsite(&self, callsite: &'static Callsite) -> NonZeroU32 {
+        register(&self.callsites, callsite)
+    }
+
+    fn span_create(&self, callsite: NonZeroU32, fields: &[Field]) -> NonZeroU32 {
+        let instance = crate::engine::next_id(&self.instance);
+        self.span_event("create", Id::new(callsite, instance), fields);
+        instance
+    }
+
+    fn span_enter(&self, id: Id) {
+        self.span_event("enter", id, &[]);
+    }
+
+    fn span_record(&self, id: Id, fields: &[Field]) {
+        self.span_event("record", id, fields);
+    }
+
+    fn span_exit(&self, id: Id) {
+        self.span_event("exit", id, &[]);
+    }
+
+    fn span_destroy(&self, id: Id) {
+        self.span_event("destroy", id, &[]);
+    }
+}
+
+impl<L: Logger> Logger for TextTraceEngine<L> {
+    fn enabled(&self, callsite: &'static LogCallsite) -> bool {
+        self.logger.enabled(callsite)
+    }
+
+    fn log(&self, callsite: &'static LogCallsite, msg: Arguments, fields: &[Field]) {
+        self.logger.log(callsite, msg, fields);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::text::TextTraceEngine;
+    use crate::field::Field;
+    use crate::logger::{Callsite as LogCallsite, Level, Logger};
+    use crate::profiler::section::{Level as SectionLevel, Section};
+    use crate::profiler::Profiler;
+    use crate::trace::span::{Callsite, Id};
+    use crate::trace::Tracer;
+    use crate::{fields, location};
+    use std::fmt::Arguments;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    #[derive(Default)]
+    struct Lines(Mutex<Vec<String>>);
+
+    impl Logger for &Lines {
+        fn log(&self, callsite: &'static LogCallsite, msg: Arguments, fields: &[Field]) {
+            let mut line = format!(
+                "[{}] {}: {}",
+                callsite.level(),
+                callsite.location().module_path(),
+                msg
+            );
+            for field in fields {
+                line += &format!(", {}={}", field.name(), field.value());
+            }
+            self.0.lock().unwrap().push(line);
+        }
+    }
+
+    #[test]
+    fn spans() {
+        static CONNECT: Callsite = Callsite::new("connect", location!());
+        let lines = Lines::default();
+        let engine = TextTraceEngine::new(&lines);
+        let callsite = engine.register_callsite(&CONNECT);
+        let instance = engine.span_create(callsite, fields!({ port = 80 }).as_ref());
+        let id = Id::new(callsite, instance);
+        engine.span_enter(id);
+        engine.span_exit(id);
+        engine.span_destroy(id);
+        assert_eq!(
+            *lines.0.lock().unwrap(),
+            [
+                "[TRACE] bp3d_debug::trace: span create connect id=0001-0001, port=80",
+                "[TRACE] bp3d_debug::trace: span enter connect id=0001-0001",
+                "[TRACE] bp3d_debug::trace: span exit connect id=0001-0001",
+                "[TRACE] bp3d_debug::trace: span destroy connect id=0001-0001"
+            ]
+        );
+    }
+
+    #[test]
+    fn sections() {
+        static STEP: Section = Section::new("physics.step", location!(), SectionLevel::Periodic);
+        let lines = Lines::default();
+        let engine = TextTraceEngine::new(&lines).min_section_duration(Duration::from_millis(1));
+        let id = engine.section_register(&STEP);
+        engine.section_record(id, 1000, 2000, &[]);
+        engine.section_record(id, 1000, 1_801_000, fields!({ bodies = 12 }).as_ref());
+        assert_eq!(
+            *lines.0.lock().unwrap(),
+            ["[TRACE] bp3d_debug::trace: section physics.step 1.8ms, bodies=12"]
+        );
+    }
+
+    #[test]
+    fn logs() {
+        static CALLSITE: LogCallsite = LogCallsite::new(location!(), Level::Info);
+        let lines = Lines::default();
+        let engine = TextTraceEngine::new(&lines);
+        engine.log(&CALLSITE, format_args!("hello"), &[]);
+        assert_eq!(
+            *lines.0.lock().unwrap(),
+            ["[INFO] bp3d_debug::engine::text::tests: hello"]
+        );
+    }
+}
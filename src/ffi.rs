@@ -0,0 +1,242 @@
+// Copyright (c) 2025, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! C interface to the debug engine, intended for native plugins loaded by a Rust host.
+//!
+//! All string arguments are copied before the function returns, so the caller keeps ownership of
+//! its buffers. Strings which must outlive the call (targets, file names, span and section names)
+//! are cached for the lifetime of the process, so they should come from a bounded set of values.
+//!
+//! A null string argument is replaced by `"unknown"`; invalid UTF-8 is replaced lossily.
+//!
+//! Sections and spans created from C report `ffi` as their module path.
+
+use crate::field::FieldSet;
+use crate::logger::Level;
+use crate::profiler::section::Section;
+use crate::trace::span::{Callsite, Id};
+use crate::util::{intern, Location};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ffi::{c_char, CStr};
+use std::num::NonZeroU64;
+use std::sync::{OnceLock, RwLock};
+
+type Entered = crate::profiler::section::Entered<'static, 0>;
+
+// (level, name or target, file, line); the level is 0 for span callsites.
+type Key<'a> = (u8, &'a str, &'a str, u32);
+
+struct Cache<V: 'static>(OnceLock<RwLock<HashMap<Key<'static>, &'static V>>>);
+
+impl<V> Cache<V> {
+    const fn new() -> Self {
+        Self(OnceLock::new())
+    }
+
+    /// Looks up `key` without allocating and only interns its strings the first time it is seen.
+    fn get_or_insert(&self, key: Key, f: impl FnOnce(Key<'static>) -> V) -> &'static V {
+        let map = self.0.get_or_init(Default::default);
+        if let Some(v) = map.read().unwrap().get(&key) {
+            return v;
+        }
+        let key = (key.0, intern(key.1), intern(key.2), key.3);
+        map.write()
+            .unwrap()
+            .entry(key)
+            .or_insert_with(|| Box::leak(Box::new(f(key))))
+    }
+}
+
+/// The module path of sections and spans created from C, which have no target argument.
+const MODULE_PATH: &str = "ffi";
+
+static LOG_CALLSITES: Cache<crate::logger::Callsite> = Cache::new();
+static SPAN_CALLSITES: Cache<Callsite> = Cache::new();
+static SECTIONS: Cache<Section> = Cache::new();
+
+unsafe fn to_str<'a>(value: *const c_char) -> Cow<'a, str> {
+    if value.is_null() {
+        return Cow::Borrowed("unknown");
+    }
+    CStr::from_ptr(value).to_string_lossy()
+}
+
+fn section_level(level: u8) -> Option<crate::profiler::section::Level> {
+    use crate::profiler::section::Level;
+    match level {
+        0 => Some(Level::Critical),
+        1 => Some(Level::Periodic),
+        2 => Some(Level::Event),
+        _ => None,
+    }
+}
+
+/// Logs a message.
+///
+/// # Arguments
+///
+/// * `level`: the level of the message (1 = trace, 2 = debug, 3 = info, 4 = warning,
+///   5 = error); messages with an invalid level are ignored.
+/// * `target`: the module path which issued the message (ex: my_plugin::loader).
+/// * `file`: the source file which issued the message.
+/// * `line`: the line in the source file which issued the message.
+/// * `msg`: a pointer to the UTF-8 message, does not need to be null-terminated.
+/// * `msg_len`: the length of the message in bytes.
+///
+/// # Safety
+///
+/// `target` and `file` must be null or valid null-terminated strings and `msg` must be null or
+/// point to at least `msg_len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn bp3d_debug_log(
+    level: u8,
+    target: *const c_char,
+    file: *const c_char,
+    line: u32,
+    msg: *const u8,
+    msg_len: usize,
+) {
+    let Ok(level) = Level::try_from(level) else {
+        return;
+    };
+    let (target, file) = (to_str(target), to_str(file));
+    let callsite = LOG_CALLSITES.get_or_insert((level as u8, &target, &file, line), |k| {
+        crate::logger::Callsite::new(Location::new(k.1, k.2, k.3), level)
+    });
+    if !callsite.is_enabled() {
+        return;
+    }
+    let msg = match msg.is_null() {
+        true => Default::default(),
+        false => String::from_utf8_lossy(std::slice::from_raw_parts(msg, msg_len)),
+    };
+    crate::engine::get().log(callsite, format_args!("{}", msg), &[]);
+}
+
+/// Begins a profiler section and returns a handle which must be passed to
+/// [bp3d_debug_section_end].
+///
+/// # Arguments
+///
+/// * `name`: the name of the section.
+/// * `file`: the source file of the section.
+/// * `line`: the line in the source file of the section.
+/// * `level`: the level of the section (0 = critical, 1 = periodic, 2 = event).
+///
+/// returns: a non-zero handle or 0 if the level is invalid.
+///
+/// # Safety
+///
+/// `name` and `file` must be null or valid null-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn bp3d_debug_section_begin(
+    name: *const c_char,
+    file: *const c_char,
+    line: u32,
+    level: u8,
+) -> u64 {
+    let Some(level) = section_level(level) else {
+        return 0;
+    };
+    let (name, file) = (to_str(name), to_str(file));
+    let section = SECTIONS.get_or_insert((level as u8, &name, &file, line), |k| {
+        Section::new(k.1, Location::new(MODULE_PATH, k.2, k.3), level)
+    });
+    Box::into_raw(Box::new(section.enter(FieldSet::new([])))) as u64
+}
+
+/// Ends a profiler section previously started with [bp3d_debug_section_begin]. A handle of 0 is
+/// ignored.
+///
+/// # Safety
+///
+/// `handle` must be 0 or a value returned by [bp3d_debug_section_begin] which was not already
+/// ended.
+#[no_mangle]
+pub unsafe extern "C" fn bp3d_debug_section_end(handle: u64) {
+    if handle != 0 {
+        drop(Box::from_raw(handle as *mut Entered));
+    }
+}
+
+/// Creates a new span and returns its identifier. The span is a child of the innermost span
+/// entered on the calling thread, if any.
+///
+/// # Arguments
+///
+/// * `name`: the name of the span.
+/// * `file`: the source file of the span.
+/// * `line`: the line in the source file of the span.
+///
+/// returns: a non-zero span identifier.
+///
+/// # Safety
+///
+/// `name` and `file` must be null or valid null-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn bp3d_debug_span_create(
+    name: *const c_char,
+    file: *const c_char,
+    line: u32,
+) -> u64 {
+    let (name, file) = (to_str(name), to_str(file));
+    let callsite = SPAN_CALLSITES.get_or_insert((0, &name, &file, line), |k| {
+        Callsite::new(k.1, Location::new(MODULE_PATH, k.2, k.3))
+    });
+    let callsite = callsite.id();
+    let parent = crate::trace::span::Span::current_id();
+    let instance = crate::engine::get().span_create_with_parent(callsite, parent, &[]);
+    Id::new(callsite, instance).into_raw().get()
+}
+
+/// Enters a span. An identifier of 0 is ignored.
+#[no_mangle]
+pub extern "C" fn bp3d_debug_span_enter(id: u64) {
+    if let Some(id) = NonZeroU64::new(id) {
+        crate::trace::span::push_current(Id::from_raw(id));
+        crate::engine::get().span_enter(Id::from_raw(id));
+    }
+}
+
+/// Exits a span. An identifier of 0 is ignored.
+#[no_mangle]
+pub extern "C" fn bp3d_debug_span_exit(id: u64) {
+    if let Some(id) = NonZeroU64::new(id) {
+        crate::trace::span::remove_current(Id::from_raw(id));
+        crate::engine::get().span_exit(Id::from_raw(id));
+    }
+}
+
+/// Destroys a span. An identifier of 0 is ignored.
+#[no_mangle]
+pub extern "C" fn bp3d_debug_span_destroy(id: u64) {
+    if let Some(id) = NonZeroU64::new(id) {
+        crate::engine::get().span_destroy(Id::from_raw(id));
+    }
+}
@@ -26,7 +26,7 @@
 // NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::fmt::{Debug, Display, Formatter};
+use core::fmt::{Debug, Display, Formatter};
 
 #[derive(Debug)]
 pub enum FieldValue<'a> {
@@ -40,7 +40,7 @@ pub enum FieldValue<'a> {
 }
 
 impl Display for FieldValue<'_> {
-    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
         match self {
             FieldValue::Int(v) => write!(f, "{}", v),
             FieldValue::UInt(v) => write!(f, "{}", v),
@@ -26,9 +26,17 @@
 // NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
 pub mod engine;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub mod field;
 pub mod logger;
 pub mod profiler;
 pub mod trace;
+#[cfg(feature = "tracing-compat")]
+pub mod tracing_compat;
 pub mod util;
+
+pub use util::Location;
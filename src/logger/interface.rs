@@ -29,16 +29,55 @@
 use crate::field::Field;
 use crate::logger::Level;
 use crate::util::Location;
-use std::fmt::Arguments;
+use core::fmt::Arguments;
+use core::sync::atomic::{AtomicU32, Ordering};
 
+// Starts at 1 so that a callsite which was never checked never matches the current epoch.
+static EPOCH: AtomicU32 = AtomicU32::new(1);
+
+/// Invalidates the [enabled](Logger::enabled) decisions cached by all callsites.
+///
+/// Engines whose filtering changes at runtime must call this after each change.
+pub fn rebuild_interest() {
+    // Release: a reader which sees the new epoch also sees the engine installed before it.
+    EPOCH.fetch_add(1, Ordering::Release);
+}
+
+#[derive(Debug)]
 pub struct Callsite {
     location: Location,
     level: Level,
+    // The epoch of the cached decision in the upper bits and the decision in the lowest bit.
+    interest: AtomicU32,
 }
 
 impl Callsite {
     pub const fn new(location: Location, level: Level) -> Self {
-        Self { location, level }
+        Self {
+            location,
+            level,
+            interest: AtomicU32::new(0),
+        }
+    }
+
+    /// Returns true if the current engine accepts messages from this callsite.
+    ///
+    /// The decision of [Logger::enabled] is cached until the next call to [rebuild_interest], so
+    /// a disabled callsite only costs a couple of atomic loads.
+    pub fn is_enabled(&'static self) -> bool {
+        #[cfg(all(feature = "std", any(test, feature = "testing")))]
+        if let Some(engine) = crate::engine::scoped() {
+            return engine.enabled(self);
+        }
+        let epoch = EPOCH.load(Ordering::Acquire) << 1;
+        let interest = self.interest.load(Ordering::Relaxed);
+        if interest & !1 == epoch {
+            return interest & 1 == 1;
+        }
+        let enabled = crate::engine::get().enabled(self);
+        self.interest
+            .store(epoch | enabled as u32, Ordering::Relaxed);
+        enabled
     }
 
     pub fn location(&self) -> &Location {
@@ -48,16 +87,36 @@ impl Callsite {
     pub fn level(&self) -> Level {
         self.level
     }
+
+    /// The target name extracted from the module path of this callsite.
+    pub fn target(&self) -> &'static str {
+        self.location.get_target_module().0
+    }
+
+    /// The module name (without the target name) extracted from the module path of this callsite.
+    pub fn module(&self) -> &'static str {
+        self.location.get_target_module().1
+    }
 }
 
 pub trait Logger {
+    /// Returns true if messages from the given callsite should be logged.
+    ///
+    /// The log macros call this before evaluating the message arguments and fields, so that
+    /// filtered messages cost nothing to format. The result is cached per callsite, see
+    /// [Callsite::is_enabled]. The default implementation accepts everything.
+    fn enabled(&self, callsite: &'static Callsite) -> bool {
+        let _ = callsite;
+        true
+    }
+
     fn log(&self, callsite: &'static Callsite, msg: Arguments, fields: &[Field]);
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::logger::Level;
-    use crate::{log, trace};
+    use crate::logger::{Callsite, Level};
+    use crate::{callsite, log, trace};
 
     #[test]
     fn api_test() {
@@ -71,4 +130,17 @@ mod tests {
         trace!("test41_42: {}, {}", tuple.0, tuple.1);
         trace!({ b }, "a boolean");
     }
+
+    #[test]
+    fn callsite_macro() {
+        static CALLSITE: &Callsite = callsite!(Level::Warn);
+        let callsite = callsite!(Level::Info);
+        assert_eq!(CALLSITE.level(), Level::Warn);
+        assert_eq!(callsite.level(), Level::Info);
+        assert_eq!(callsite.target(), "bp3d_debug");
+        assert_eq!(callsite.module(), "logger::interface::tests");
+        let i = 42;
+        crate::engine::get().log(CALLSITE, format_args!("test: {}", i), &[]);
+        crate::engine::get().log(callsite, format_args!("test: {}", i), &[]);
+    }
 }
@@ -26,7 +26,8 @@
 // NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::fmt::{Display, Formatter};
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
 
 /// An enum representing the available verbosity levels for a message.
 #[repr(u8)]
@@ -73,7 +74,147 @@ impl Level {
 }
 
 impl Display for Level {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         f.write_str(self.as_str())
     }
 }
+
+/// The error returned when a string or an integer does not name a [Level].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ParseLevelError;
+
+impl Display for ParseLevelError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str("invalid log level")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseLevelError {}
+
+impl FromStr for Level {
+    type Err = ParseLevelError;
+
+    /// Parses a level name case-insensitively; both "warn" and "warning" are accepted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const NAMES: [(&str, Level); 6] = [
+            ("trace", Level::Trace),
+            ("debug", Level::Debug),
+            ("info", Level::Info),
+            ("warn", Level::Warn),
+            ("warning", Level::Warn),
+            ("error", Level::Error),
+        ];
+        NAMES
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s))
+            .map(|(_, level)| *level)
+            .ok_or(ParseLevelError)
+    }
+}
+
+impl TryFrom<u8> for Level {
+    type Error = ParseLevelError;
+
+    fn try_from(value: u8) -> Result<Self, ParseLevelError> {
+        match value {
+            1 => Ok(Level::Trace),
+            2 => Ok(Level::Debug),
+            3 => Ok(Level::Info),
+            4 => Ok(Level::Warn),
+            5 => Ok(Level::Error),
+            _ => Err(ParseLevelError),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Level {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Level {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl serde::de::Visitor<'_> for Visitor {
+            type Value = Level;
+
+            fn expecting(&self, f: &mut Formatter) -> core::fmt::Result {
+                f.write_str("a log level name or number")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                v.parse()
+                    .map_err(|_| E::invalid_value(serde::de::Unexpected::Str(v), &self))
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                u8::try_from(v)
+                    .ok()
+                    .and_then(|v| Level::try_from(v).ok())
+                    .ok_or_else(|| E::invalid_value(serde::de::Unexpected::Unsigned(v), &self))
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::logger::level::ParseLevelError;
+    use crate::logger::Level;
+
+    #[test]
+    fn from_str() {
+        assert_eq!("trace".parse(), Ok(Level::Trace));
+        assert_eq!("DEBUG".parse(), Ok(Level::Debug));
+        assert_eq!("Info".parse(), Ok(Level::Info));
+        assert_eq!("warn".parse(), Ok(Level::Warn));
+        assert_eq!("WARNING".parse(), Ok(Level::Warn));
+        assert_eq!("error".parse(), Ok(Level::Error));
+        assert_eq!("off".parse::<Level>(), Err(ParseLevelError));
+        assert_eq!("".parse::<Level>(), Err(ParseLevelError));
+        for level in [
+            Level::Trace,
+            Level::Debug,
+            Level::Info,
+            Level::Warn,
+            Level::Error,
+        ] {
+            assert_eq!(level.as_str().parse(), Ok(level));
+        }
+    }
+
+    #[test]
+    fn try_from_u8() {
+        for level in [
+            Level::Trace,
+            Level::Debug,
+            Level::Info,
+            Level::Warn,
+            Level::Error,
+        ] {
+            assert_eq!(Level::try_from(level as u8), Ok(level));
+        }
+        assert_eq!(Level::try_from(0), Err(ParseLevelError));
+        assert_eq!(Level::try_from(6), Err(ParseLevelError));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        assert_eq!(serde_json::to_string(&Level::Warn).unwrap(), "\"WARNING\"");
+        assert_eq!(
+            serde_json::from_str::<Level>("\"warn\"").unwrap(),
+            Level::Warn
+        );
+        assert_eq!(serde_json::from_str::<Level>("2").unwrap(), Level::Debug);
+        assert!(serde_json::from_str::<Level>("\"off\"").is_err());
+        assert!(serde_json::from_str::<Level>("256").is_err());
+    }
+}
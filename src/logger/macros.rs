@@ -26,18 +26,37 @@
 // NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+/// Generate a `&'static` [Callsite](crate::logger::Callsite) for the given level at the location
+/// of the macro invocation.
+///
+/// The expansion is a constant expression, so it can be used both in function scope and to
+/// initialize a `static`.
+#[macro_export]
+macro_rules! callsite {
+    ($level: expr) => {
+        {
+            static _CALLSITE: $crate::logger::Callsite = $crate::logger::Callsite::new($crate::location!(), $level);
+            &_CALLSITE
+        }
+    };
+}
+
 #[macro_export]
 macro_rules! log {
     ($level: expr, $({$($field: tt)*})*, $msg: literal $(,$($args: expr),*)?) => {
         {
-            static _CALLSITE: $crate::logger::Callsite = $crate::logger::Callsite::new($crate::location!(), $level);
-            $crate::engine::get().log(&_CALLSITE, format_args!($msg $(, $($args),*)?), &[$($crate::field!($($field)*),)*]);
+            let callsite = $crate::callsite!($level);
+            if callsite.is_enabled() {
+                $crate::engine::get().log(callsite, format_args!($msg $(, $($args),*)?), &[$($crate::field!($($field)*),)*]);
+            }
         }
     };
     ($level: expr, $msg: literal $(,$($args: expr),*)?) => {
         {
-            static _CALLSITE: $crate::logger::Callsite = $crate::logger::Callsite::new($crate::location!(), $level);
-            $crate::engine::get().log(&_CALLSITE, format_args!($msg $(, $($args),*)?), &[]);
+            let callsite = $crate::callsite!($level);
+            if callsite.is_enabled() {
+                $crate::engine::get().log(callsite, format_args!($msg $(, $($args),*)?), &[]);
+            }
         }
     };
 }
@@ -31,4 +31,4 @@ mod level;
 pub mod macros;
 
 pub use interface::*;
-pub use level::Level;
+pub use level::{Level, ParseLevelError};
@@ -56,12 +56,12 @@ impl DurationNewUnchecked for Duration {
 #[repr(transparent)]
 pub struct Instant(Duration);
 
-#[cfg(windows)]
+#[cfg(not(any(unix, all(target_family = "wasm", target_os = "unknown"))))]
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
 pub struct Instant(std::time::Instant);
 
-#[cfg(windows)]
+#[cfg(not(any(unix, all(target_family = "wasm", target_os = "unknown"))))]
 impl Instant {
     #[inline(always)]
     pub fn now() -> Self {
@@ -74,6 +74,25 @@ impl Instant {
     }
 }
 
+// There is no clock available on wasm32-unknown-unknown without a JavaScript host, so sections
+// recorded on this target always have a zero duration.
+#[cfg(all(target_family = "wasm", target_os = "unknown"))]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Instant;
+
+#[cfg(all(target_family = "wasm", target_os = "unknown"))]
+impl Instant {
+    #[inline(always)]
+    pub fn now() -> Self {
+        Self
+    }
+
+    #[inline(always)]
+    pub fn elapsed(&self) -> Duration {
+        Duration::ZERO
+    }
+}
+
 #[cfg(unix)]
 impl Instant {
     pub fn now() -> Self {
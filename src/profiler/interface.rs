@@ -28,7 +28,7 @@
 
 use crate::field::Field;
 use crate::profiler::section::Section;
-use std::num::NonZeroU32;
+use core::num::NonZeroU32;
 
 pub trait Profiler {
     fn section_register(&self, section: &'static Section) -> NonZeroU32;
@@ -26,16 +26,54 @@
 // NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+#[doc(hidden)]
 #[macro_export]
-macro_rules! profiler_section_start {
-    ($name: ident $(: $parent: ident)?, $level: expr, $({$($field: tt)*})*) => {
-        static $name: $crate::profiler::section::Section = $crate::profiler::section::Section::new(stringify!($name), $crate::location!(), $level)
+macro_rules! __profiler_section_static {
+    ($name: ident, $display: expr, $level: expr $(, $parent: ident)?) => {
+        static $name: $crate::profiler::section::Section = $crate::profiler::section::Section::new($display, $crate::location!(), $level)
             $(.set_parent(&$parent))?;
-        let _section = $name.enter($crate::field::FieldSet::new([$($crate::field!($($field)*),)*]));
     };
-    ($name: ident $(: $parent: ident)?, $level: expr) => {
-        static $name: $crate::profiler::section::Section = $crate::profiler::section::Section::new(stringify!($name), $crate::location!(), $level)
-            $(.set_parent(&$parent))?;
-        let _section = $name.enter($crate::field::FieldSet::new([]));
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __profiler_section_enter {
+    ($section: expr $(, $({$($field: tt)*})*)?) => {
+        $section.enter($crate::field::FieldSet::new([$($($crate::field!($($field)*),)*)?]))
+    };
+}
+
+#[macro_export]
+macro_rules! profiler_section_start {
+    ($name: ident $(: $parent: ident)?, $level: expr $(, $({$($field: tt)*})*)?) => {
+        $crate::__profiler_section_static!($name, stringify!($name), $level $(, $parent)?);
+        let _section = $crate::__profiler_section_enter!($name $(, $({$($field)*})*)?);
+    };
+}
+
+/// Enters a profiler section and returns the guard which records the section when dropped.
+///
+/// Unlike [profiler_section_start](crate::profiler_section_start), this macro is an expression,
+/// so the caller controls the lifetime of the section. The underlying section is a static unique
+/// to the macro invocation.
+///
+/// # Examples
+///
+/// ```
+/// use bp3d_debug::profiler::section::Level;
+/// use bp3d_debug::profiler_section;
+///
+/// let guard = profiler_section!("load", Level::Event);
+/// drop(guard);
+/// let value = 42;
+/// let _guard = profiler_section!("process", Level::Event, {value} {step = 1});
+/// ```
+#[macro_export]
+macro_rules! profiler_section {
+    ($name: literal, $level: expr $(, $({$($field: tt)*})*)?) => {
+        {
+            $crate::__profiler_section_static!(_SECTION, $name, $level);
+            $crate::__profiler_section_enter!(_SECTION $(, $({$($field)*})*)?)
+        }
     };
 }
@@ -28,7 +28,12 @@
 
 mod interface;
 mod macros;
+#[cfg(feature = "std")]
+pub mod registry;
 pub mod section;
-mod instant;
+#[cfg(feature = "std")]
+pub(crate) mod table;
+#[cfg(feature = "std")]
+pub(crate) mod instant;
 
 pub use interface::*;
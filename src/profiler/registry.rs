@@ -0,0 +1,291 @@
+// Copyright (c) 2025, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! A profiler keeping the latest timings of each section for display in the local process.
+
+use crate::field::Field;
+use crate::profiler::section::{Level, Section};
+use crate::profiler::table::SectionTable;
+use crate::profiler::Profiler;
+use std::num::NonZeroU32;
+use std::sync::atomic::{fence, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+struct Slot {
+    section: &'static Section,
+    // Odd while a record is being written.
+    seq: AtomicU64,
+    start: AtomicU64,
+    end: AtomicU64,
+    count: AtomicU64,
+    window: Box<[AtomicU64]>,
+    fields: Mutex<Vec<(String, String)>>,
+}
+
+impl Slot {
+    fn new(section: &'static Section, window: usize) -> Self {
+        Self {
+            section,
+            seq: AtomicU64::new(0),
+            start: AtomicU64::new(0),
+            end: AtomicU64::new(0),
+            count: AtomicU64::new(0),
+            window: (0..window).map(|_| AtomicU64::new(0)).collect(),
+            fields: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn record(&self, start: u64, end: u64, fields: &[Field]) {
+        // Writers of the same section exclude each other by moving the sequence to odd.
+        let mut seq = self.seq.load(Ordering::Relaxed);
+        loop {
+            if seq & 1 == 1 {
+                std::hint::spin_loop();
+                seq = self.seq.load(Ordering::Relaxed);
+                continue;
+            }
+            match self
+                .seq
+                .compare_exchange_weak(seq, seq + 1, Ordering::Acquire, Ordering::Relaxed)
+            {
+                Ok(_) => break,
+                Err(v) => seq = v,
+            }
+        }
+        fence(Ordering::Release);
+        let count = self.count.load(Ordering::Relaxed);
+        self.start.store(start, Ordering::Relaxed);
+        self.end.store(end, Ordering::Relaxed);
+        if !self.window.is_empty() {
+            self.window[(count % self.window.len() as u64) as usize]
+                .store(end.saturating_sub(start), Ordering::Relaxed);
+        }
+        self.count.store(count + 1, Ordering::Relaxed);
+        self.seq.store(seq + 2, Ordering::Release);
+        // Never wait for a reader to release the fields.
+        if let Ok(mut v) = self.fields.try_lock() {
+            v.clear();
+            v.extend(
+                fields
+                    .iter()
+                    .map(|v| (v.name().into(), v.value().to_string())),
+            );
+        }
+    }
+
+    fn snapshot(&self) -> SectionSnapshot {
+        let mut durations = Vec::with_capacity(self.window.len());
+        let (start, end, count) = loop {
+            let seq = self.seq.load(Ordering::Acquire);
+            if seq & 1 == 1 {
+                std::hint::spin_loop();
+                continue;
+            }
+            let start = self.start.load(Ordering::Relaxed);
+            let end = self.end.load(Ordering::Relaxed);
+            let count = self.count.load(Ordering::Relaxed);
+            let len = count.min(self.window.len() as u64);
+            durations.clear();
+            for i in count - len..count {
+                let v =
+                    self.window[(i % self.window.len() as u64) as usize].load(Ordering::Relaxed);
+                durations.push(Duration::from_nanos(v));
+            }
+            fence(Ordering::Acquire);
+            if self.seq.load(Ordering::Relaxed) == seq {
+                break (start, end, count);
+            }
+        };
+        SectionSnapshot {
+            name: self.section.name(),
+            level: self.section.level(),
+            parent: self.section.parent().map(|v| v.name()),
+            count,
+            start,
+            end,
+            durations,
+            fields: self.fields.lock().unwrap().clone(),
+        }
+    }
+}
+
+/// A snapshot of the latest timings of a section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionSnapshot {
+    /// The name of the section.
+    pub name: &'static str,
+
+    /// The level of the section.
+    pub level: Level,
+
+    /// The name of the parent section if any.
+    pub parent: Option<&'static str>,
+
+    /// The number of times the section was recorded.
+    pub count: u64,
+
+    /// The start time in nanoseconds of the most recent record.
+    pub start: u64,
+
+    /// The end time in nanoseconds of the most recent record.
+    pub end: u64,
+
+    /// The durations of the most recent records, oldest first.
+    pub durations: Vec<Duration>,
+
+    /// The fields of the most recent record, formatted as strings.
+    pub fields: Vec<(String, String)>,
+}
+
+/// A profiler which keeps the most recent timings of each section so that they can be displayed
+/// by the process itself, for example in a debug overlay.
+///
+/// Each section keeps its last record and a rolling window of its last durations. Recording uses
+/// a sequence lock per section, so [snapshot](SectionRegistry::snapshot) never blocks a section
+/// being recorded and never observes a partially written record. Fields are updated on a best
+/// effort basis: if a snapshot is reading them at the same time, the fields of that record are
+/// skipped. Log messages and spans are discarded.
+pub struct SectionRegistry {
+    slots: SectionTable<Slot>,
+    window: usize,
+}
+
+impl SectionRegistry {
+    /// Creates a new registry able to track up to `capacity` sections, each keeping its last
+    /// `window` durations.
+    pub fn new(capacity: usize, window: usize) -> Self {
+        Self {
+            slots: SectionTable::new(capacity),
+            window,
+        }
+    }
+
+    /// Returns a snapshot of every registered section, in registration order.
+    pub fn snapshot(&self) -> Vec<SectionSnapshot> {
+        self.slots.iter().map(Slot::snapshot).collect()
+    }
+}
+
+impl Profiler for SectionRegistry {
+    fn section_register(&self, section: &'static Section) -> NonZeroU32 {
+        self.slots.register(|| Slot::new(section, self.window))
+    }
+
+    fn section_record(&self, id: NonZeroU32, start: u64, end: u64, fields: &[Field]) {
+        if let Some(slot) = self.slots.get(id) {
+            slot.record(start, end, fields);
+        }
+    }
+}
+
+crate::engine::discard!(Tracer for SectionRegistry);
+crate::engine::discard!(Logger for SectionRegistry);
+
+#[cfg(test)]
+mod tests {
+    use crate::location;
+    use crate::profiler::registry::SectionRegistry;
+    use crate::profiler::section::{Level, Section};
+    use crate::profiler::Profiler;
+    use crate::{fields, profiler_section};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn window() {
+        static PARENT: Section = Section::new("parent", location!(), Level::Periodic);
+        static SECTION: Section =
+            Section::new("section", location!(), Level::Critical).set_parent(&PARENT);
+        let registry = SectionRegistry::new(4, 3);
+        let id = registry.section_register(&SECTION);
+        for i in 1..=5 {
+            registry.section_record(id, 100 * i, 100 * i + i, fields!({ i }).as_ref());
+        }
+        let snapshot = registry.snapshot();
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[0].name, "section");
+        assert_eq!(snapshot[0].level, Level::Critical);
+        assert_eq!(snapshot[0].parent, Some("parent"));
+        assert_eq!(snapshot[0].count, 5);
+        assert_eq!((snapshot[0].start, snapshot[0].end), (500, 505));
+        assert_eq!(snapshot[0].durations, [3, 4, 5].map(Duration::from_nanos));
+        assert_eq!(snapshot[0].fields, [("i".into(), "5".into())]);
+    }
+
+    #[test]
+    fn scoped() {
+        let registry: &'static SectionRegistry = Box::leak(Box::new(SectionRegistry::new(4, 2)));
+        crate::engine::with_scoped(registry, || {
+            drop(profiler_section!("scoped", Level::Event, { frame = 1 }));
+        });
+        let snapshot = registry.snapshot();
+        assert_eq!(snapshot[0].name, "scoped");
+        assert_eq!(snapshot[0].count, 1);
+        assert_eq!(snapshot[0].fields, [("frame".into(), "1".into())]);
+    }
+
+    #[test]
+    fn stress() {
+        static SECTION: Section = Section::new("stress", location!(), Level::Critical);
+        let registry = SectionRegistry::new(1, 8);
+        let id = registry.section_register(&SECTION);
+        let done = AtomicBool::new(false);
+        std::thread::scope(|s| {
+            let reader = s.spawn(|| {
+                while !done.load(Ordering::Relaxed) {
+                    for snapshot in registry.snapshot() {
+                        assert_eq!(snapshot.end, snapshot.start * 3);
+                        if let Some(last) = snapshot.durations.last() {
+                            assert_eq!(last.as_nanos() as u64, snapshot.start * 2);
+                        }
+                        assert_eq!(snapshot.durations.len() as u64, snapshot.count.min(8));
+                    }
+                }
+            });
+            let writers: Vec<_> = (0..4)
+                .map(|t| {
+                    let registry = &registry;
+                    s.spawn(move || {
+                        for i in 1..20000u64 {
+                            // Every record satisfies end == 3 * start, so a torn read is detectable.
+                            let start = i * 4 + t;
+                            registry.section_record(id, start, start * 3, &[]);
+                        }
+                    })
+                })
+                .collect();
+            for writer in writers {
+                writer.join().unwrap();
+            }
+            done.store(true, Ordering::Relaxed);
+            reader.join().unwrap();
+        });
+        assert_eq!(registry.snapshot()[0].count, 4 * 19999);
+    }
+}
@@ -27,10 +27,8 @@
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::field::FieldSet;
-use crate::util::Location;
-use std::num::NonZeroU32;
-use std::sync::OnceLock;
-use super::instant::Instant;
+use crate::util::{Location, OnceCell};
+use core::num::NonZeroU32;
 
 #[repr(u8)]
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -45,10 +43,6 @@ pub enum Level {
     Event = 2,
 }
 
-thread_local! {
-    static CUR_TIME: Instant = Instant::now();
-}
-
 pub struct Entered<'a, const N: usize> {
     id: NonZeroU32,
     start: u64,
@@ -57,7 +51,7 @@ pub struct Entered<'a, const N: usize> {
 
 impl<const N: usize> Drop for Entered<'_, N> {
     fn drop(&mut self) {
-        let end = CUR_TIME.with(|v| v.elapsed().as_nanos() as _);
+        let end = crate::util::clock().monotonic_ns();
         crate::engine::get().section_record(self.id, self.start, end, self.fields.as_ref());
     }
 }
@@ -67,7 +61,7 @@ pub struct Section {
     location: Location,
     level: Level,
     parent: Option<&'static Section>,
-    id: OnceLock<NonZeroU32>,
+    id: OnceCell<NonZeroU32>,
 }
 
 impl Section {
@@ -77,7 +71,7 @@ impl Section {
             location,
             level,
             parent: None,
-            id: OnceLock::new(),
+            id: OnceCell::new(),
         }
     }
 
@@ -107,11 +101,20 @@ impl Section {
             .get_or_init(|| crate::engine::get().section_register(self))
     }
 
+    pub(crate) fn id(&'static self) -> NonZeroU32 {
+        #[cfg(all(feature = "std", any(test, feature = "testing")))]
+        if let Some(id) =
+            crate::engine::scoped_id(self as *const Self as usize, |v| v.section_register(self))
+        {
+            return id;
+        }
+        *self.get_id()
+    }
+
     pub fn enter<'a, const N: usize>(&'static self, fields: FieldSet<'a, N>) -> Entered<'a, N> {
-        let id = self.get_id();
         Entered {
-            id: *id,
-            start: CUR_TIME.with(|v| v.elapsed().as_nanos() as _),
+            id: self.id(),
+            start: crate::util::clock().monotonic_ns(),
             fields,
         }
     }
@@ -121,7 +124,7 @@ impl Section {
 mod tests {
     use crate::field::FieldSet;
     use crate::profiler::section::{Level, Section};
-    use crate::{fields, location, profiler_section_start};
+    use crate::{fields, location, profiler_section, profiler_section_start};
 
     #[test]
     fn basic() {
@@ -154,4 +157,17 @@ mod tests {
         profiler_section_start!(API2_TEST: API_TEST, Level::Event);
         profiler_section_start!(API3_TEST_WITH_PARAMS: API2_TEST, Level::Event, {value} {str} {?lvl} {test=value});
     }
+
+    #[test]
+    fn expression() {
+        let value = 32;
+        let guard = profiler_section!("early_drop", Level::Event);
+        drop(guard);
+        let mut first = None;
+        for i in 0..4 {
+            let _guard = profiler_section!("loop", Level::Periodic, {i} {value} {test = "test"});
+            let guard = profiler_section!("loop", Level::Periodic);
+            assert_eq!(*first.get_or_insert(guard.id), guard.id);
+        }
+    }
 }
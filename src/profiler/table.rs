@@ -0,0 +1,71 @@
+// Copyright (c) 2025, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! A fixed-capacity table of per-section state shared by the section-based engines.
+
+use std::num::NonZeroU32;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::OnceLock;
+
+/// A table handing out one slot per registered section, indexed by section id.
+///
+/// Registering and looking up a section never locks. Sections registered past the capacity
+/// receive [NonZeroU32::MAX] as id, which never maps to a slot.
+pub(crate) struct SectionTable<T> {
+    slots: Box<[OnceLock<T>]>,
+    next: AtomicU32,
+}
+
+impl<T> SectionTable<T> {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            slots: (0..capacity).map(|_| OnceLock::new()).collect(),
+            next: AtomicU32::new(0),
+        }
+    }
+
+    /// Reserves the next slot, initializing it with `f`, and returns its section id.
+    pub(crate) fn register(&self, f: impl FnOnce() -> T) -> NonZeroU32 {
+        let index = self.next.fetch_add(1, Ordering::Relaxed);
+        if let Some(slot) = self.slots.get(index as usize) {
+            // Each index is handed out once, so the slot is always empty here.
+            let _ = slot.set(f());
+        }
+        NonZeroU32::new(index.wrapping_add(1)).unwrap_or(NonZeroU32::MAX)
+    }
+
+    /// Returns the slot of the given section id, if it was handed out by this table.
+    pub(crate) fn get(&self, id: NonZeroU32) -> Option<&T> {
+        self.slots.get(id.get() as usize - 1).and_then(|v| v.get())
+    }
+
+    /// Iterates over the registered slots in registration order.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &T> {
+        self.slots.iter().filter_map(|v| v.get())
+    }
+}
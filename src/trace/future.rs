@@ -26,41 +26,46 @@
 // NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use crate::trace::span::{Entered, Span};
+use crate::trace::span::Span;
 use crate::trace::Trace;
-use std::future::Future;
-use std::pin::Pin;
-use std::task::{Context, Poll};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
 
+/// A future which enters its span for the duration of each poll.
+///
+/// The span is not entered while the future is parked, so polls happening on different threads
+/// each produce a matching enter/exit pair. The span is destroyed when the future completes.
 pub struct TracedFuture<F> {
     future: F,
-    span: Option<Entered>,
+    span: Option<Span>,
 }
 
 impl<F: Future> Future for TracedFuture<F> {
     type Output = F::Output;
 
-    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        unsafe {
-            let pin = Pin::new_unchecked(&mut self.future);
-            let value = pin.poll(cx);
-            if value.is_ready() {
-                drop(self.span.take());
-            }
-            value
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `future` is structurally pinned: it is never moved out of the pinned struct,
+        // TracedFuture does not implement Drop and is only Unpin when F is. `span` is not pinned.
+        let this = unsafe { self.get_unchecked_mut() };
+        let future = unsafe { Pin::new_unchecked(&mut this.future) };
+        let entered = this.span.as_ref().map(|v| v.enter());
+        let value = future.poll(cx);
+        drop(entered);
+        if value.is_ready() {
+            drop(this.span.take());
         }
+        value
     }
 }
 
-impl<F> Unpin for TracedFuture<F> {}
-
 impl<F: Future> Trace for F {
     type Output = TracedFuture<F>;
 
     fn trace(self, span: Span) -> Self::Output {
         TracedFuture {
             future: self,
-            span: Some(span.enter()),
+            span: Some(span),
         }
     }
 }
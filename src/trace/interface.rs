@@ -28,11 +28,26 @@
 
 use crate::field::Field;
 use crate::trace::span::{Callsite, Id, Span};
-use std::num::NonZeroU32;
+use core::num::NonZeroU32;
 
 pub trait Tracer {
     fn register_callsite(&self, callsite: &'static Callsite) -> NonZeroU32;
     fn span_create(&self, callsite: NonZeroU32, fields: &[Field]) -> NonZeroU32;
+
+    /// Creates a span which is a child of `parent`, or a root span if `parent` is [None].
+    ///
+    /// The default implementation ignores the parent and delegates to
+    /// [span_create](Tracer::span_create).
+    fn span_create_with_parent(
+        &self,
+        callsite: NonZeroU32,
+        parent: Option<Id>,
+        fields: &[Field],
+    ) -> NonZeroU32 {
+        let _ = parent;
+        self.span_create(callsite, fields)
+    }
+
     fn span_enter(&self, id: Id);
     fn span_record(&self, id: Id, fields: &[Field]);
     fn span_exit(&self, id: Id);
@@ -27,9 +27,9 @@
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use crate::field::Field;
-use crate::util::Location;
-use std::num::{NonZeroU32, NonZeroU64};
-use std::sync::OnceLock;
+use crate::util::{Location, OnceCell};
+use core::marker::PhantomData;
+use core::num::{NonZeroU32, NonZeroU64};
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 #[repr(transparent)]
@@ -62,7 +62,7 @@ impl Id {
 pub struct Callsite {
     name: &'static str,
     location: Location,
-    id: OnceLock<NonZeroU32>,
+    id: OnceCell<NonZeroU32>,
 }
 
 impl Callsite {
@@ -70,7 +70,7 @@ impl Callsite {
         Self {
             name,
             location,
-            id: OnceLock::new(),
+            id: OnceCell::new(),
         }
     }
 
@@ -86,14 +86,65 @@ impl Callsite {
         self.id
             .get_or_init(|| crate::engine::get().register_callsite(self))
     }
+
+    pub(crate) fn id(&'static self) -> NonZeroU32 {
+        #[cfg(all(feature = "std", any(test, feature = "testing")))]
+        if let Some(id) =
+            crate::engine::scoped_id(self as *const Self as usize, |v| v.register_callsite(self))
+        {
+            return id;
+        }
+        *self.get_id()
+    }
+}
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    static STACK: core::cell::RefCell<Vec<Id>> = const { core::cell::RefCell::new(Vec::new()) };
+}
+
+/// Pushes a span on the span stack of the current thread.
+///
+/// The stack is ignored while thread-local storage is being destroyed.
+#[cfg(feature = "std")]
+pub(crate) fn push_current(id: Id) {
+    let _ = STACK.try_with(|stack| stack.borrow_mut().push(id));
 }
 
+/// Removes the innermost occurrence of a span from the span stack of the current thread.
+#[cfg(feature = "std")]
+pub(crate) fn remove_current(id: Id) {
+    // Guards may be dropped out of order, so remove the innermost occurrence of this span
+    // rather than blindly popping the top of the stack.
+    let _ = STACK.try_with(|stack| {
+        let mut stack = stack.borrow_mut();
+        if let Some(pos) = stack.iter().rposition(|v| *v == id) {
+            stack.remove(pos);
+        }
+    });
+}
+
+/// A guard which exits its span when dropped.
+///
+/// The guard must be dropped on the thread which entered the span, so it is not [Send]:
+///
+/// ```compile_fail
+/// use bp3d_debug::span;
+///
+/// let span = span!(WORKER);
+/// let entered = span.enter();
+/// std::thread::spawn(move || drop(entered));
+/// ```
 pub struct Entered {
     id: Id,
+    // The span stack is thread-local.
+    _not_send: PhantomData<*const ()>,
 }
 
 impl Drop for Entered {
     fn drop(&mut self) {
+        #[cfg(feature = "std")]
+        remove_current(self.id);
         crate::engine::get().span_exit(self.id);
     }
 }
@@ -103,20 +154,45 @@ pub struct Span {
 }
 
 impl Span {
-    pub fn with_fields(callsite: &'static Callsite, fields: &[Field]) -> Self {
-        let callsite = *callsite.get_id();
-        let instance = crate::engine::get().span_create(callsite, fields);
+    fn create(callsite: &'static Callsite, parent: Option<Id>, fields: &[Field]) -> Self {
+        let callsite = callsite.id();
+        let instance = crate::engine::get().span_create_with_parent(callsite, parent, fields);
         Self {
             id: Id::new(callsite, instance),
         }
     }
 
+    /// Creates a new span with the given fields, as a child of the [current](Span::current_id)
+    /// span if any.
+    pub fn with_fields(callsite: &'static Callsite, fields: &[Field]) -> Self {
+        Self::create(callsite, Self::current_id(), fields)
+    }
+
+    /// Creates a new span, as a child of the [current](Span::current_id) span if any.
     pub fn new(callsite: &'static Callsite) -> Self {
-        let callsite = *callsite.get_id();
-        let instance = crate::engine::get().span_create(callsite, &[]);
-        Self {
-            id: Id::new(callsite, instance),
-        }
+        Self::create(callsite, Self::current_id(), &[])
+    }
+
+    /// Creates a new span with the given fields as a child of an explicit parent.
+    pub fn child_of(callsite: &'static Callsite, parent: Id, fields: &[Field]) -> Self {
+        Self::create(callsite, Some(parent), fields)
+    }
+
+    /// Returns the id of the innermost span entered on the current thread.
+    ///
+    /// Without the `std` feature there is no span stack and this always returns [None].
+    pub fn current_id() -> Option<Id> {
+        #[cfg(feature = "std")]
+        return STACK
+            .try_with(|stack| stack.borrow().last().copied())
+            .ok()
+            .flatten();
+        #[cfg(not(feature = "std"))]
+        None
+    }
+
+    pub fn id(&self) -> Id {
+        self.id
     }
 
     pub fn record(&self, fields: &[Field]) {
@@ -124,7 +200,13 @@ impl Span {
     }
 
     pub fn enter(&self) -> Entered {
-        Entered { id: self.id }
+        #[cfg(feature = "std")]
+        push_current(self.id);
+        crate::engine::get().span_enter(self.id);
+        Entered {
+            id: self.id,
+            _not_send: PhantomData,
+        }
     }
 }
 
@@ -149,4 +231,107 @@ mod tests {
         span.record(fields!({ test2 = str }).as_ref());
         let _entered = span.enter();
     }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn stack() {
+        use crate::location;
+        use crate::trace::span::{Callsite, Span};
+
+        static CHILD: Callsite = Callsite::new("child", location!());
+        let outer = span!(STACK_OUTER);
+        assert_eq!(Span::current_id(), None);
+        let entered_outer = outer.enter();
+        assert_eq!(Span::current_id(), Some(outer.id()));
+        let inner = span!(STACK_INNER);
+        let entered_inner = inner.enter();
+        assert_eq!(Span::current_id(), Some(inner.id()));
+        let child = Span::child_of(&CHILD, outer.id(), &[]);
+        {
+            let _entered = child.enter();
+            assert_eq!(Span::current_id(), Some(child.id()));
+        }
+        assert_eq!(Span::current_id(), Some(inner.id()));
+        // Dropping the outer guard first must not remove the inner span from the stack.
+        drop(entered_outer);
+        assert_eq!(Span::current_id(), Some(inner.id()));
+        drop(entered_inner);
+        assert_eq!(Span::current_id(), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn parent() {
+        use crate::location;
+        use crate::trace::span::{Callsite, Span};
+
+        static CHILD: Callsite = Callsite::new("child", location!());
+        use crate::field::Field;
+        use crate::logger::{Callsite as LogCallsite, Logger};
+        use crate::profiler::section::Section;
+        use crate::profiler::Profiler;
+        use crate::trace::span::Id;
+        use crate::trace::Tracer;
+        use core::fmt::Arguments;
+        use core::num::NonZeroU32;
+        use std::sync::Mutex;
+
+        #[derive(Default)]
+        struct Parents(Mutex<Vec<Option<Id>>>);
+
+        impl Profiler for Parents {
+            fn section_register(&self, _: &'static Section) -> NonZeroU32 {
+                NonZeroU32::MIN
+            }
+
+            fn section_record(&self, _: NonZeroU32, _: u64, _: u64, _: &[Field]) {}
+        }
+
+        impl Tracer for Parents {
+            fn register_callsite(&self, _: &'static Callsite) -> NonZeroU32 {
+                NonZeroU32::MIN
+            }
+
+            fn span_create(&self, callsite: NonZeroU32, fields: &[Field]) -> NonZeroU32 {
+                self.span_create_with_parent(callsite, None, fields)
+            }
+
+            fn span_create_with_parent(
+                &self,
+                _: NonZeroU32,
+                parent: Option<Id>,
+                _: &[Field],
+            ) -> NonZeroU32 {
+                let mut parents = self.0.lock().unwrap();
+                parents.push(parent);
+                NonZeroU32::new(parents.len() as _).unwrap()
+            }
+
+            fn span_enter(&self, _: Id) {}
+
+            fn span_record(&self, _: Id, _: &[Field]) {}
+
+            fn span_exit(&self, _: Id) {}
+
+            fn span_destroy(&self, _: Id) {}
+        }
+
+        impl Logger for Parents {
+            fn log(&self, _: &'static LogCallsite, _: Arguments, _: &[Field]) {}
+        }
+
+        let engine: &'static Parents = Box::leak(Box::default());
+        crate::engine::with_scoped(engine, || {
+            let root = span!(PARENT_ROOT);
+            let _root = root.enter();
+            let nested = span!(PARENT_NESTED);
+            let _nested = nested.enter();
+            let _leaf = span!(PARENT_LEAF);
+            let _explicit = Span::child_of(&CHILD, root.id(), &[]);
+            assert_eq!(
+                *engine.0.lock().unwrap(),
+                [None, Some(root.id()), Some(nested.id()), Some(root.id())]
+            );
+        });
+    }
 }
@@ -0,0 +1,310 @@
+// Copyright (c) 2025, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! A bridge forwarding spans and events emitted with the [tracing](https://docs.rs/tracing)
+//! crate to the installed engine.
+
+use crate::field::Field;
+use crate::logger::Level;
+use crate::trace::span::{Callsite, Id, Span};
+use crate::util::Location;
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock, RwLock};
+use tracing_core::callsite::Identifier;
+use tracing_core::field::Visit;
+use tracing_core::span::{Attributes, Record};
+use tracing_core::{Event, Interest, Metadata};
+
+struct Raw(String);
+
+impl Debug for Raw {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+enum Value {
+    Int(i64),
+    UInt(u64),
+    Double(f64),
+    Boolean(bool),
+    String(String),
+    Debug(Raw),
+}
+
+#[derive(Default)]
+struct Visitor {
+    message: Option<String>,
+    values: Vec<(&'static str, Value)>,
+}
+
+impl Visitor {
+    fn fields(&self) -> Vec<Field<'_>> {
+        self.values
+            .iter()
+            .map(|(name, value)| match value {
+                Value::Int(v) => Field::new(name, *v),
+                Value::UInt(v) => Field::new(name, *v),
+                Value::Double(v) => Field::new(name, *v),
+                Value::Boolean(v) => Field::new(name, *v),
+                Value::String(v) => Field::new(name, &**v),
+                Value::Debug(v) => Field::new_debug(name, v),
+            })
+            .collect()
+    }
+}
+
+impl Visit for Visitor {
+    fn record_f64(&mut self, field: &tracing_core::Field, value: f64) {
+        self.values.push((field.name(), Value::Double(value)));
+    }
+
+    fn record_i64(&mut self, field: &tracing_core::Field, value: i64) {
+        self.values.push((field.name(), Value::Int(value)));
+    }
+
+    fn record_u64(&mut self, field: &tracing_core::Field, value: u64) {
+        self.values.push((field.name(), Value::UInt(value)));
+    }
+
+    fn record_bool(&mut self, field: &tracing_core::Field, value: bool) {
+        self.values.push((field.name(), Value::Boolean(value)));
+    }
+
+    fn record_str(&mut self, field: &tracing_core::Field, value: &str) {
+        if field.name() == "message" {
+            self.message = Some(value.into());
+        } else {
+            self.values
+                .push((field.name(), Value::String(value.into())));
+        }
+    }
+
+    fn record_debug(&mut self, field: &tracing_core::Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            self.message = Some(format!("{:?}", value));
+        } else {
+            self.values
+                .push((field.name(), Value::Debug(Raw(format!("{:?}", value)))));
+        }
+    }
+}
+
+fn level(level: &tracing_core::Level) -> Level {
+    match *level {
+        tracing_core::Level::TRACE => Level::Trace,
+        tracing_core::Level::DEBUG => Level::Debug,
+        tracing_core::Level::INFO => Level::Info,
+        tracing_core::Level::WARN => Level::Warn,
+        _ => Level::Error,
+    }
+}
+
+fn location(metadata: &'static Metadata<'static>) -> Location {
+    Location::new(
+        metadata.module_path().unwrap_or(metadata.target()),
+        metadata.file().unwrap_or("unknown"),
+        metadata.line().unwrap_or_default(),
+    )
+}
+
+/// The callsites created for tracing callsites, shared by all bridges.
+struct Callsites<V: 'static>(OnceLock<RwLock<HashMap<Identifier, &'static V>>>);
+
+impl<V> Callsites<V> {
+    const fn new() -> Self {
+        Self(OnceLock::new())
+    }
+
+    fn get(&self, key: &Identifier) -> Option<&'static V> {
+        self.0.get()?.read().unwrap().get(key).copied()
+    }
+
+    fn get_or_insert(&self, key: Identifier, f: impl FnOnce() -> V) -> &'static V {
+        if let Some(v) = self.get(&key) {
+            return v;
+        }
+        self.0
+            .get_or_init(Default::default)
+            .write()
+            .unwrap()
+            .entry(key)
+            .or_insert_with(|| Box::leak(Box::new(f())))
+    }
+}
+
+static SPAN_CALLSITES: Callsites<Callsite> = Callsites::new();
+static LOG_CALLSITES: Callsites<crate::logger::Callsite> = Callsites::new();
+
+fn span_callsite(metadata: &'static Metadata<'static>) -> &'static Callsite {
+    SPAN_CALLSITES.get_or_insert(metadata.callsite(), || {
+        Callsite::new(metadata.name(), location(metadata))
+    })
+}
+
+fn log_callsite(metadata: &'static Metadata<'static>) -> &'static crate::logger::Callsite {
+    LOG_CALLSITES.get_or_insert(metadata.callsite(), || {
+        crate::logger::Callsite::new(location(metadata), level(metadata.level()))
+    })
+}
+
+/// A [Subscriber](tracing_core::Subscriber) which forwards spans and events to the engine
+/// returned by [engine::get](crate::engine::get).
+///
+/// Events are forwarded to the [Logger](crate::logger::Logger) with the `message` field as the
+/// log message and spans are forwarded to the [Tracer](crate::trace::Tracer). Values recorded as
+/// integers, floats, booleans and strings keep their type, other values are formatted with their
+/// [Debug] implementation. Spans and events are filtered with
+/// [Logger::enabled](crate::logger::Logger::enabled) using the level of their tracing callsite.
+///
+/// The bridge hands out its own span identifiers and maps them to the engine identifiers, so
+/// the identifiers seen by tracing are never reused while the bridge is alive.
+///
+/// Callsites are created once for each tracing callsite, shared by all bridges, and are never
+/// freed.
+pub struct TracingBridge {
+    next_id: AtomicU64,
+    spans: Mutex<HashMap<u64, (Id, usize)>>,
+}
+
+impl Default for TracingBridge {
+    fn default() -> Self {
+        Self {
+            next_id: AtomicU64::new(1),
+            spans: Default::default(),
+        }
+    }
+}
+
+impl TracingBridge {
+    /// Creates a new bridge.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn span(&self, id: &tracing_core::span::Id) -> Option<Id> {
+        self.spans
+            .lock()
+            .unwrap()
+            .get(&id.into_u64())
+            .map(|(id, _)| *id)
+    }
+}
+
+impl tracing_core::Subscriber for TracingBridge {
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        log_callsite(metadata);
+        // The installed engine may change, so the decision is never cached by tracing.
+        Interest::sometimes()
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        LOG_CALLSITES
+            .get(&metadata.callsite())
+            .map(|v| v.is_enabled())
+            .unwrap_or(true)
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> tracing_core::span::Id {
+        let callsite = span_callsite(span.metadata()).id();
+        let mut visitor = Visitor::default();
+        span.record(&mut visitor);
+        let parent = if let Some(parent) = span.parent() {
+            self.span(parent)
+        } else if span.is_contextual() {
+            Span::current_id()
+        } else {
+            None
+        };
+        let instance =
+            crate::engine::get().span_create_with_parent(callsite, parent, &visitor.fields());
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.spans
+            .lock()
+            .unwrap()
+            .insert(id, (Id::new(callsite, instance), 1));
+        tracing_core::span::Id::from_u64(id)
+    }
+
+    fn record(&self, span: &tracing_core::span::Id, values: &Record<'_>) {
+        let Some(id) = self.span(span) else {
+            return;
+        };
+        let mut visitor = Visitor::default();
+        values.record(&mut visitor);
+        crate::engine::get().span_record(id, &visitor.fields());
+    }
+
+    fn record_follows_from(&self, _: &tracing_core::span::Id, _: &tracing_core::span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let callsite = log_callsite(event.metadata());
+        let mut visitor = Visitor::default();
+        event.record(&mut visitor);
+        let message = visitor.message.as_deref().unwrap_or_default();
+        crate::engine::get().log(callsite, format_args!("{}", message), &visitor.fields());
+    }
+
+    fn enter(&self, span: &tracing_core::span::Id) {
+        if let Some(id) = self.span(span) {
+            crate::trace::span::push_current(id);
+            crate::engine::get().span_enter(id);
+        }
+    }
+
+    fn exit(&self, span: &tracing_core::span::Id) {
+        if let Some(id) = self.span(span) {
+            crate::trace::span::remove_current(id);
+            crate::engine::get().span_exit(id);
+        }
+    }
+
+    fn clone_span(&self, id: &tracing_core::span::Id) -> tracing_core::span::Id {
+        if let Some((_, count)) = self.spans.lock().unwrap().get_mut(&id.into_u64()) {
+            *count += 1;
+        }
+        id.clone()
+    }
+
+    fn try_close(&self, id: tracing_core::span::Id) -> bool {
+        let mut spans = self.spans.lock().unwrap();
+        let Some((_, count)) = spans.get_mut(&id.into_u64()) else {
+            return false;
+        };
+        *count -= 1;
+        if *count > 0 {
+            return false;
+        }
+        let (id, _) = spans.remove(&id.into_u64()).unwrap();
+        drop(spans);
+        crate::engine::get().span_destroy(id);
+        true
+    }
+}
@@ -26,6 +26,9 @@
 // NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+#[cfg(feature = "std")]
+use core::sync::atomic::{AtomicPtr, Ordering};
+
 /// Extracts the target name and the module path (without the target name) from a full module path string.
 ///
 /// # Arguments
@@ -42,12 +45,45 @@ pub fn extract_target_module(base_string: &str) -> (&str, &str) {
     (target, module.unwrap_or("main"))
 }
 
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+const fn fnv1a_bytes(mut hash: u64, bytes: &[u8]) -> u64 {
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+        i += 1;
+    }
+    hash
+}
+
+/// Computes a stable hash of a source location at compile time.
+///
+/// The hash is a 64 bits FNV-1a over the file name, the line and the column; it is stable across
+/// builds and platforms which makes it suitable as a deduplication key.
+///
+/// # Arguments
+///
+/// * `file`: the source file.
+/// * `line`: the line number in the source file.
+/// * `column`: the column number in the source file.
+///
+/// returns: u64
+pub const fn callsite_hash(file: &str, line: u32, column: u32) -> u64 {
+    let hash = fnv1a_bytes(FNV_OFFSET_BASIS, file.as_bytes());
+    let hash = fnv1a_bytes(hash, &line.to_le_bytes());
+    fnv1a_bytes(hash, &column.to_le_bytes())
+}
+
 /// The context of a log message.
 #[derive(Clone, Copy, Debug)]
 pub struct Location {
     module_path: &'static str,
     file: &'static str,
     line: u32,
+    column: u32,
+    hash: u64,
 }
 
 impl Location {
@@ -57,16 +93,39 @@ impl Location {
     ///
     /// # Arguments
     ///
-    /// * `module_path`: the module path obtained from the [module_path](module_path) macro.
-    /// * `file`: the source file obtained from the [file](file) macro.
-    /// * `line`: the line number in the source file obtained from the [line](line) macro.
+    /// * `module_path`: the module path obtained from the [module_path] macro.
+    /// * `file`: the source file obtained from the [file] macro.
+    /// * `line`: the line number in the source file obtained from the [line] macro.
     ///
     /// returns: Metadata
     pub const fn new(module_path: &'static str, file: &'static str, line: u32) -> Self {
+        Self::with_column(module_path, file, line, 0)
+    }
+
+    /// Creates a new instance of a log message location including the column in the source file.
+    ///
+    /// This function is const to let the caller store location structures in statics.
+    ///
+    /// # Arguments
+    ///
+    /// * `module_path`: the module path obtained from the [module_path] macro.
+    /// * `file`: the source file obtained from the [file] macro.
+    /// * `line`: the line number in the source file obtained from the [line] macro.
+    /// * `column`: the column number in the source file obtained from the [column] macro.
+    ///
+    /// returns: Metadata
+    pub const fn with_column(
+        module_path: &'static str,
+        file: &'static str,
+        line: u32,
+        column: u32,
+    ) -> Self {
         Self {
             module_path,
             file,
             line,
+            column,
+            hash: callsite_hash(file, line, column),
         }
     }
 
@@ -85,16 +144,333 @@ impl Location {
         self.line
     }
 
+    /// The column in the source file which issued this log message.
+    pub fn column(&self) -> u32 {
+        self.column
+    }
+
+    /// A stable hash of the file, line and column of this location, computed at compile time.
+    pub fn callsite_hash(&self) -> u64 {
+        self.hash
+    }
+
     /// Extracts the target name and the module name from the module path.
     pub fn get_target_module(&self) -> (&'static str, &'static str) {
         extract_target_module(self.module_path)
     }
 }
 
+#[cfg(feature = "std")]
+impl Location {
+    /// Creates a new instance of a location from strings which are not static, such as strings
+    /// received from foreign code.
+    ///
+    /// The strings are interned for the lifetime of the process, so they should come from a
+    /// bounded set of values.
+    ///
+    /// # Arguments
+    ///
+    /// * `module_path`: the module path.
+    /// * `file`: the source file.
+    /// * `line`: the line number in the source file.
+    ///
+    /// returns: Location
+    pub fn new_dynamic(module_path: &str, file: &str, line: u32) -> Self {
+        Self::new(intern(module_path), intern(file), line)
+    }
+}
+
+/// A cell initialized at most once, which parks racing threads when the standard library is
+/// available and spins otherwise.
+pub(crate) struct OnceCell<T>(
+    #[cfg(feature = "std")] std::sync::OnceLock<T>,
+    #[cfg(not(feature = "std"))] spin::Once<T>,
+);
+
+impl<T> OnceCell<T> {
+    pub(crate) const fn new() -> Self {
+        #[cfg(feature = "std")]
+        return Self(std::sync::OnceLock::new());
+        #[cfg(not(feature = "std"))]
+        Self(spin::Once::new())
+    }
+
+    pub(crate) fn get_or_init(&self, f: impl FnOnce() -> T) -> &T {
+        #[cfg(feature = "std")]
+        return self.0.get_or_init(f);
+        #[cfg(not(feature = "std"))]
+        self.0.call_once(f)
+    }
+}
+
+#[cfg(feature = "std")]
+static INTERN_SOFT_CAP: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(4096);
+
+#[cfg(feature = "std")]
+static STRINGS: std::sync::RwLock<Option<std::collections::HashSet<&'static str>>> =
+    std::sync::RwLock::new(None);
+
+/// Sets the number of interned strings above which a warning is logged, 4096 by default.
+///
+/// Interned strings are never freed, so growing past this limit usually means dynamic values
+/// are passed where a bounded set of names is expected. Interning still succeeds past the limit.
+#[cfg(feature = "std")]
+pub fn set_intern_soft_cap(cap: usize) {
+    INTERN_SOFT_CAP.store(cap, Ordering::Relaxed);
+}
+
+/// Returns a static string equal to `value`, allocating it only the first time it is seen.
+#[cfg(feature = "std")]
+pub(crate) fn intern(value: &str) -> &'static str {
+    static WARNED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+    if let Some(v) = STRINGS.read().unwrap().as_ref().and_then(|v| v.get(value)) {
+        return v;
+    }
+    let mut guard = STRINGS.write().unwrap();
+    let strings = guard.get_or_insert_with(Default::default);
+    if let Some(v) = strings.get(value) {
+        return v;
+    }
+    let v: &'static str = Box::leak(value.into());
+    strings.insert(v);
+    let cap = INTERN_SOFT_CAP.load(Ordering::Relaxed);
+    let warn = strings.len() > cap && !WARNED.swap(true, Ordering::Relaxed);
+    // The engine may intern strings while logging, so the lock must be released first.
+    drop(guard);
+    if warn {
+        crate::warning!("string interner exceeded its soft cap of {} entries", cap);
+    }
+    v
+}
+
 /// Generate a [Location](crate::Location) structure.
 #[macro_export]
 macro_rules! location {
     () => {
-        $crate::util::Location::new(module_path!(), file!(), line!())
+        $crate::util::Location::with_column(module_path!(), file!(), line!(), column!())
     };
 }
+
+/// A source of time shared by the instrumentation code.
+pub trait Clock: Sync {
+    /// The current wall clock time.
+    ///
+    /// The default implementation offsets the unix epoch by [monotonic_ns](Clock::monotonic_ns).
+    #[cfg(feature = "std")]
+    fn wall(&self) -> std::time::SystemTime {
+        std::time::UNIX_EPOCH + core::time::Duration::from_nanos(self.monotonic_ns())
+    }
+
+    /// The number of nanoseconds elapsed since an arbitrary, process-wide, epoch.
+    ///
+    /// This clock must never go backwards.
+    fn monotonic_ns(&self) -> u64;
+}
+
+/// The default [Clock] based on the system clocks.
+#[cfg(feature = "std")]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn wall(&self) -> std::time::SystemTime {
+        std::time::SystemTime::now()
+    }
+
+    fn monotonic_ns(&self) -> u64 {
+        static EPOCH: std::sync::OnceLock<crate::profiler::instant::Instant> =
+            std::sync::OnceLock::new();
+        EPOCH
+            .get_or_init(crate::profiler::instant::Instant::now)
+            .elapsed()
+            .as_nanos() as _
+    }
+}
+
+/// The default [Clock] without the standard library, time stands still until a clock is
+/// installed with [set_clock].
+#[cfg(not(feature = "std"))]
+pub struct NullClock;
+
+#[cfg(not(feature = "std"))]
+impl Clock for NullClock {
+    fn monotonic_ns(&self) -> u64 {
+        0
+    }
+}
+
+// A thin pointer to a leaked fat reference so that reading the clock is a single atomic load.
+#[cfg(feature = "std")]
+static CLOCK: AtomicPtr<&'static dyn Clock> = AtomicPtr::new(core::ptr::null_mut());
+
+#[cfg(not(feature = "std"))]
+static CLOCK: spin::Once<&'static dyn Clock> = spin::Once::new();
+
+/// Returns the clock currently in use, [SystemClock] unless replaced by [set_clock].
+#[cfg(feature = "std")]
+pub fn clock() -> &'static dyn Clock {
+    let ptr = CLOCK.load(Ordering::Acquire);
+    if ptr.is_null() {
+        &SystemClock
+    } else {
+        unsafe { *ptr }
+    }
+}
+
+/// Returns the clock currently in use, [NullClock] unless replaced by [set_clock].
+#[cfg(not(feature = "std"))]
+pub fn clock() -> &'static dyn Clock {
+    CLOCK.get().copied().unwrap_or(&NullClock)
+}
+
+/// Replaces the clock used by profiler sections and engines.
+///
+/// Timestamps taken before the replacement are not converted, so this should be called before
+/// any instrumented code runs, except in tests. Without the standard library, the clock can
+/// only be set once.
+///
+/// returns: true if the clock was replaced.
+pub fn set_clock(clock: &'static dyn Clock) -> bool {
+    #[cfg(feature = "std")]
+    {
+        // Another thread may still be reading the previous clock, so the leaked references are
+        // never freed; they are reused when the same clock is installed again.
+        static LEAKED: std::sync::Mutex<Vec<&'static &'static dyn Clock>> =
+            std::sync::Mutex::new(Vec::new());
+        let mut leaked = LEAKED.lock().unwrap_or_else(|e| e.into_inner());
+        let ptr = match leaked.iter().find(|v| core::ptr::addr_eq(**v, clock)) {
+            Some(v) => *v,
+            None => {
+                let v: &'static &'static dyn Clock = Box::leak(Box::new(clock));
+                leaked.push(v);
+                v
+            }
+        };
+        CLOCK.store(ptr as *const _ as *mut _, Ordering::Release);
+        true
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        let mut set = false;
+        CLOCK.call_once(|| {
+            set = true;
+            clock
+        });
+        set
+    }
+}
+
+/// A [Clock] which only moves when asked to, for deterministic tests.
+#[cfg(all(feature = "std", any(test, feature = "testing")))]
+pub struct ManualClock {
+    nanos: core::sync::atomic::AtomicU64,
+}
+
+#[cfg(all(feature = "std", any(test, feature = "testing")))]
+impl ManualClock {
+    /// Creates a new manual clock starting at the unix epoch.
+    pub const fn new() -> Self {
+        Self {
+            nanos: core::sync::atomic::AtomicU64::new(0),
+        }
+    }
+
+    /// Moves the clock forward.
+    pub fn advance(&self, duration: core::time::Duration) {
+        self.nanos
+            .fetch_add(duration.as_nanos() as _, Ordering::Relaxed);
+    }
+}
+
+#[cfg(all(feature = "std", any(test, feature = "testing")))]
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(all(feature = "std", any(test, feature = "testing")))]
+impl Clock for ManualClock {
+    fn monotonic_ns(&self) -> u64 {
+        self.nanos.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn column() {
+        let (column, loc) = (column!(), location!());
+        assert_eq!(loc.line(), line!() - 1);
+        assert_eq!(loc.column(), column + "column!(), ".len() as u32);
+    }
+
+    #[test]
+    fn root_reexport() {
+        let loc: crate::Location = location!();
+        let callsite = crate::logger::Callsite::new(loc, crate::logger::Level::Info);
+        assert_eq!(callsite.location().column(), loc.column());
+    }
+
+    #[test]
+    fn callsite_hash() {
+        #[rustfmt::skip]
+        let (a, b) = (location!(), location!());
+        assert_eq!(a.line(), b.line());
+        assert_ne!(a.column(), b.column());
+        assert_ne!(a.callsite_hash(), b.callsite_hash());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn intern() {
+        use super::{intern, STRINGS};
+        let len = || {
+            STRINGS
+                .read()
+                .unwrap()
+                .as_ref()
+                .map(|v| v.len())
+                .unwrap_or_default()
+        };
+        let ptr = intern(&String::from("intern::test")).as_ptr();
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                std::thread::spawn(|| {
+                    (0..100)
+                        .map(|_| intern(&String::from("intern::test")).as_ptr() as usize)
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        let before = len();
+        for thread in threads {
+            assert!(thread.join().unwrap().iter().all(|v| *v == ptr as usize));
+        }
+        assert_eq!(len(), before);
+        let location = super::Location::new_dynamic("intern::test", "intern.rs", 1);
+        assert_eq!(location.module_path().as_ptr(), ptr);
+        assert_eq!(location.file(), "intern.rs");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn manual_clock() {
+        use super::{Clock, ManualClock};
+        use std::time::{Duration, UNIX_EPOCH};
+        let clock = ManualClock::new();
+        assert_eq!(clock.monotonic_ns(), 0);
+        clock.advance(Duration::from_millis(3));
+        assert_eq!(clock.monotonic_ns(), 3_000_000);
+        assert_eq!(clock.wall(), UNIX_EPOCH + Duration::from_millis(3));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn system_clock() {
+        use super::{Clock, SystemClock};
+        let a = SystemClock.monotonic_ns();
+        let b = SystemClock.monotonic_ns();
+        assert!(b >= a);
+    }
+}
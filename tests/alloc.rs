@@ -0,0 +1,188 @@
+// Copyright (c) 2025, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Checks that the instrumentation hot paths never allocate.
+
+use bp3d_debug::field::Field;
+use bp3d_debug::logger::{Callsite as LogCallsite, Level, Logger};
+use bp3d_debug::profiler::section::{Level as SectionLevel, Section};
+use bp3d_debug::profiler::Profiler;
+use bp3d_debug::trace::span::{Callsite, Id};
+use bp3d_debug::trace::Tracer;
+use bp3d_debug::{debug, info, profiler_section, span};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt::{Arguments, Write};
+use std::num::NonZeroU32;
+use std::sync::Once;
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|v| v.set(v.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|v| v.set(v.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// An engine which only accepts messages at Info level or above and formats them into a fixed
+/// buffer, so that only the instrumentation itself is measured.
+struct FilterEngine;
+
+struct Buffer([u8; 256], usize);
+
+impl Write for Buffer {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        let len = s.len().min(self.0.len() - self.1);
+        self.0[self.1..self.1 + len].copy_from_slice(&s.as_bytes()[..len]);
+        self.1 += len;
+        Ok(())
+    }
+}
+
+impl Profiler for FilterEngine {
+    fn section_register(&self, _: &'static Section) -> NonZeroU32 {
+        NonZeroU32::MIN
+    }
+
+    fn section_record(&self, _: NonZeroU32, _: u64, _: u64, _: &[Field]) {}
+}
+
+impl Tracer for FilterEngine {
+    fn register_callsite(&self, _: &'static Callsite) -> NonZeroU32 {
+        NonZeroU32::MIN
+    }
+
+    fn span_create(&self, _: NonZeroU32, _: &[Field]) -> NonZeroU32 {
+        NonZeroU32::MIN
+    }
+
+    fn span_enter(&self, _: Id) {}
+
+    fn span_record(&self, _: Id, _: &[Field]) {}
+
+    fn span_exit(&self, _: Id) {}
+
+    fn span_destroy(&self, _: Id) {}
+}
+
+impl Logger for FilterEngine {
+    fn enabled(&self, callsite: &'static LogCallsite) -> bool {
+        callsite.level() >= Level::Info
+    }
+
+    fn log(&self, _: &'static LogCallsite, msg: Arguments, fields: &[Field]) {
+        let mut buffer = Buffer([0; 256], 0);
+        let _ = buffer.write_fmt(msg);
+        for field in fields {
+            let _ = write!(buffer, ", {}={}", field.name(), field.value());
+        }
+    }
+}
+
+fn assert_no_alloc(name: &str, f: impl Fn()) {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| assert!(bp3d_debug::engine::set(&FilterEngine)));
+    // The first call registers callsites and initializes the clock.
+    f();
+    let before = ALLOCATIONS.with(|v| v.get());
+    f();
+    let count = ALLOCATIONS.with(|v| v.get()) - before;
+    assert_eq!(count, 0, "{} allocated {} time(s)", name, count);
+}
+
+#[test]
+fn log() {
+    assert_no_alloc("info!", || {
+        let value = 42;
+        info!({ value } {test = "test"}, "a message: {}", value);
+    });
+}
+
+#[test]
+fn disabled_log() {
+    assert_no_alloc("debug!", || {
+        let value = 42;
+        debug!({ value } {test = "test"}, "a message: {}", value);
+    });
+    assert!(!bp3d_debug::callsite!(Level::Debug).is_enabled());
+}
+
+#[test]
+fn section() {
+    assert_no_alloc("Section::enter", || {
+        let value = 42;
+        let _section = profiler_section!("alloc", SectionLevel::Critical, {value} {test = 4.2});
+    });
+}
+
+#[test]
+fn span() {
+    assert_no_alloc("Span::new", || {
+        let value = 42;
+        let span = span!(ALLOC, { value });
+        let _entered = span.enter();
+        span.record(&[Field::new("test", "test")]);
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn aggregating_profiler() {
+    static SECTION: Section =
+        Section::new("alloc", bp3d_debug::location!(), SectionLevel::Critical);
+    let profiler = bp3d_debug::engine::profiling::AggregatingProfiler::default();
+    let id = profiler.section_register(&SECTION);
+    assert_no_alloc("AggregatingProfiler::section_record", || {
+        profiler.section_record(id, 0, 42, &[]);
+    });
+}
+
+#[test]
+fn callsite() {
+    static CALLSITE: LogCallsite = LogCallsite::new(bp3d_debug::location!(), Level::Info);
+    assert_no_alloc("Logger::log", || {
+        bp3d_debug::engine::get().log(&CALLSITE, format_args!("{}", 42), &[]);
+    });
+}
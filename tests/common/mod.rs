@@ -0,0 +1,178 @@
+// Copyright (c) 2025, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+// Not every test binary uses every helper.
+#![allow(dead_code)]
+
+use bp3d_debug::field::Field;
+use bp3d_debug::logger::{Callsite as LogCallsite, Level, Logger};
+use bp3d_debug::profiler::section::Section;
+use bp3d_debug::profiler::Profiler;
+use bp3d_debug::trace::span::{Callsite, Id};
+use bp3d_debug::trace::Tracer;
+use std::fmt::Arguments;
+use std::num::NonZeroU32;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Mutex, Once};
+
+pub type Fields = Vec<(String, String)>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    Log(Level, &'static str, &'static str, u32, String, Fields),
+    Section(&'static str, u64, u64),
+    SpanCreate(Id, &'static str, Fields),
+    SpanParent(Id, Id),
+    SpanEnter(Id),
+    SpanRecord(Id, Fields),
+    SpanExit(Id),
+    SpanDestroy(Id),
+}
+
+fn fields(fields: &[Field]) -> Fields {
+    fields
+        .iter()
+        .map(|v| (v.name().into(), v.value().to_string()))
+        .collect()
+}
+
+/// An engine capturing everything it receives, shared by all tests of a test binary.
+pub struct Capture {
+    ids: AtomicU32,
+    sections: Mutex<Vec<&'static Section>>,
+    callsites: Mutex<Vec<&'static Callsite>>,
+    events: Mutex<Vec<Event>>,
+}
+
+impl Capture {
+    fn push(&self, event: Event) {
+        self.events.lock().unwrap().push(event);
+    }
+
+    pub fn contains(&self, event: &Event) -> bool {
+        self.events.lock().unwrap().contains(event)
+    }
+
+    pub fn events(&self) -> Vec<Event> {
+        self.events.lock().unwrap().clone()
+    }
+}
+
+impl Profiler for Capture {
+    fn section_register(&self, section: &'static Section) -> NonZeroU32 {
+        let mut sections = self.sections.lock().unwrap();
+        sections.push(section);
+        NonZeroU32::new(sections.len() as _).unwrap()
+    }
+
+    fn section_record(&self, id: NonZeroU32, start: u64, end: u64, _: &[Field]) {
+        let section = self.sections.lock().unwrap()[id.get() as usize - 1];
+        self.push(Event::Section(section.name(), start, end));
+    }
+}
+
+impl Tracer for Capture {
+    fn register_callsite(&self, callsite: &'static Callsite) -> NonZeroU32 {
+        let mut callsites = self.callsites.lock().unwrap();
+        callsites.push(callsite);
+        NonZeroU32::new(callsites.len() as _).unwrap()
+    }
+
+    fn span_create(&self, callsite: NonZeroU32, fields: &[Field]) -> NonZeroU32 {
+        let site = self.callsites.lock().unwrap()[callsite.get() as usize - 1];
+        let instance = NonZeroU32::new(self.ids.fetch_add(1, Ordering::Relaxed)).unwrap();
+        self.push(Event::SpanCreate(
+            Id::new(callsite, instance),
+            site.name(),
+            self::fields(fields),
+        ));
+        instance
+    }
+
+    fn span_create_with_parent(
+        &self,
+        callsite: NonZeroU32,
+        parent: Option<Id>,
+        fields: &[Field],
+    ) -> NonZeroU32 {
+        let instance = self.span_create(callsite, fields);
+        if let Some(parent) = parent {
+            self.push(Event::SpanParent(Id::new(callsite, instance), parent));
+        }
+        instance
+    }
+
+    fn span_enter(&self, id: Id) {
+        self.push(Event::SpanEnter(id));
+    }
+
+    fn span_record(&self, id: Id, fields: &[Field]) {
+        self.push(Event::SpanRecord(id, self::fields(fields)));
+    }
+
+    fn span_exit(&self, id: Id) {
+        self.push(Event::SpanExit(id));
+    }
+
+    fn span_destroy(&self, id: Id) {
+        self.push(Event::SpanDestroy(id));
+    }
+}
+
+impl Logger for Capture {
+    // Trace level is disabled so tests can check that filtering is honoured.
+    fn enabled(&self, callsite: &'static LogCallsite) -> bool {
+        callsite.level() > Level::Trace
+    }
+
+    fn log(&self, callsite: &'static LogCallsite, msg: Arguments, fields: &[Field]) {
+        let location = callsite.location();
+        self.push(Event::Log(
+            callsite.level(),
+            location.module_path(),
+            location.file(),
+            location.line(),
+            msg.to_string(),
+            self::fields(fields),
+        ));
+    }
+}
+
+static CAPTURE: Capture = Capture {
+    ids: AtomicU32::new(1),
+    sections: Mutex::new(Vec::new()),
+    callsites: Mutex::new(Vec::new()),
+    events: Mutex::new(Vec::new()),
+};
+
+/// Installs the capture engine on first use and returns it.
+pub fn capture() -> &'static Capture {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| assert!(bp3d_debug::engine::set(&CAPTURE)));
+    &CAPTURE
+}
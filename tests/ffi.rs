@@ -0,0 +1,151 @@
+// Copyright (c) 2025, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+#![cfg(feature = "ffi")]
+
+mod common;
+
+use bp3d_debug::ffi::*;
+use bp3d_debug::logger::Level;
+use bp3d_debug::trace::span::{Id, Span};
+use common::{capture, Event};
+use std::num::NonZeroU64;
+
+#[test]
+fn log() {
+    let capture = capture();
+    let msg = "hello from C";
+    unsafe {
+        bp3d_debug_log(
+            4,
+            c"plugin::loader".as_ptr(),
+            c"loader.c".as_ptr(),
+            12,
+            msg.as_ptr(),
+            msg.len(),
+        );
+        bp3d_debug_log(
+            3,
+            std::ptr::null(),
+            std::ptr::null(),
+            1,
+            std::ptr::null(),
+            0,
+        );
+        // Trace messages are rejected by the capture engine.
+        bp3d_debug_log(
+            1,
+            c"plugin::loader".as_ptr(),
+            c"loader.c".as_ptr(),
+            3,
+            msg.as_ptr(),
+            msg.len(),
+        );
+        bp3d_debug_log(
+            42,
+            std::ptr::null(),
+            std::ptr::null(),
+            2,
+            msg.as_ptr(),
+            msg.len(),
+        );
+    }
+    assert!(capture.contains(&Event::Log(
+        Level::Warn,
+        "plugin::loader",
+        "loader.c",
+        12,
+        "hello from C".into(),
+        vec![]
+    )));
+    assert!(capture.contains(&Event::Log(
+        Level::Info,
+        "unknown",
+        "unknown",
+        1,
+        "".into(),
+        vec![]
+    )));
+    let events = capture.events();
+    assert!(!events.iter().any(|v| matches!(
+        v,
+        Event::Log(_, "unknown", _, 2, _, _) | Event::Log(_, _, _, 3, _, _)
+    )));
+}
+
+#[test]
+fn section() {
+    let capture = capture();
+    unsafe {
+        assert_eq!(
+            bp3d_debug_section_begin(c"ffi_section".as_ptr(), c"s.c".as_ptr(), 1, 42),
+            0
+        );
+        bp3d_debug_section_end(0);
+        let handle = bp3d_debug_section_begin(c"ffi_section".as_ptr(), c"s.c".as_ptr(), 1, 2);
+        assert_ne!(handle, 0);
+        bp3d_debug_section_end(handle);
+    }
+    let events = capture.events();
+    assert!(events
+        .iter()
+        .any(|v| matches!(v, Event::Section("ffi_section", start, end) if end >= start)));
+}
+
+#[test]
+fn span() {
+    let capture = capture();
+    let id = unsafe { bp3d_debug_span_create(c"ffi_span".as_ptr(), c"s.c".as_ptr(), 2) };
+    bp3d_debug_span_enter(id);
+    bp3d_debug_span_exit(id);
+    bp3d_debug_span_destroy(id);
+    bp3d_debug_span_destroy(0);
+    let id = Id::from_raw(std::num::NonZeroU64::new(id).unwrap());
+    assert!(capture.contains(&Event::SpanCreate(id, "ffi_span", vec![])));
+    assert!(capture.contains(&Event::SpanEnter(id)));
+    assert!(capture.contains(&Event::SpanExit(id)));
+    assert!(capture.contains(&Event::SpanDestroy(id)));
+}
+
+#[test]
+fn span_parent() {
+    let capture = capture();
+    let parent = unsafe { bp3d_debug_span_create(c"ffi_parent".as_ptr(), c"s.c".as_ptr(), 3) };
+    bp3d_debug_span_enter(parent);
+    let child = unsafe { bp3d_debug_span_create(c"ffi_child".as_ptr(), c"s.c".as_ptr(), 4) };
+    assert_eq!(
+        Span::current_id(),
+        Some(Id::from_raw(NonZeroU64::new(parent).unwrap()))
+    );
+    bp3d_debug_span_exit(parent);
+    assert_eq!(Span::current_id(), None);
+    bp3d_debug_span_destroy(child);
+    bp3d_debug_span_destroy(parent);
+    let [parent, child] = [parent, child].map(|v| Id::from_raw(NonZeroU64::new(v).unwrap()));
+    assert!(capture.contains(&Event::SpanParent(child, parent)));
+}
@@ -0,0 +1,140 @@
+// Copyright (c) 2025, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use bp3d_debug::field::Field;
+use bp3d_debug::logger::{Callsite as LogCallsite, Level, Logger};
+use bp3d_debug::profiler::section::Section;
+use bp3d_debug::profiler::Profiler;
+use bp3d_debug::trace::span::{Callsite, Id};
+use bp3d_debug::trace::Tracer;
+use bp3d_debug::{debug, info, log, warning};
+use std::fmt::{Arguments, Debug, Formatter};
+use std::num::NonZeroU32;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Once;
+
+static FORMATTED: AtomicUsize = AtomicUsize::new(0);
+static LOGGED: AtomicUsize = AtomicUsize::new(0);
+static CHECKED: AtomicUsize = AtomicUsize::new(0);
+
+/// An engine which only accepts messages at Info level or above.
+struct InfoEngine;
+
+impl Profiler for InfoEngine {
+    fn section_register(&self, _: &'static Section) -> NonZeroU32 {
+        NonZeroU32::MIN
+    }
+
+    fn section_record(&self, _: NonZeroU32, _: u64, _: u64, _: &[Field]) {}
+}
+
+impl Tracer for InfoEngine {
+    fn register_callsite(&self, _: &'static Callsite) -> NonZeroU32 {
+        NonZeroU32::MIN
+    }
+
+    fn span_create(&self, _: NonZeroU32, _: &[Field]) -> NonZeroU32 {
+        NonZeroU32::MIN
+    }
+
+    fn span_enter(&self, _: Id) {}
+
+    fn span_record(&self, _: Id, _: &[Field]) {}
+
+    fn span_exit(&self, _: Id) {}
+
+    fn span_destroy(&self, _: Id) {}
+}
+
+impl Logger for InfoEngine {
+    fn enabled(&self, callsite: &'static LogCallsite) -> bool {
+        // Only the interest test logs at trace level.
+        if callsite.level() == Level::Trace {
+            CHECKED.fetch_add(1, Ordering::Relaxed);
+        }
+        callsite.level() >= Level::Info
+    }
+
+    fn log(&self, _: &'static LogCallsite, msg: Arguments, fields: &[Field]) {
+        for field in fields {
+            let _ = field.value().to_string();
+        }
+        let _ = msg.to_string();
+        LOGGED.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// A value which counts how many times it is formatted.
+struct Expensive;
+
+impl Debug for Expensive {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        FORMATTED.fetch_add(1, Ordering::Relaxed);
+        f.write_str("Expensive")
+    }
+}
+
+fn expensive(evaluated: &AtomicUsize) -> Expensive {
+    evaluated.fetch_add(1, Ordering::Relaxed);
+    Expensive
+}
+
+fn install() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| assert!(bp3d_debug::engine::set(&InfoEngine)));
+}
+
+#[test]
+fn filtered() {
+    install();
+    let evaluated = AtomicUsize::new(0);
+    debug!({ value = ?expensive(&evaluated) }, "filtered: {:?}", expensive(&evaluated));
+    debug!("filtered: {:?}", expensive(&evaluated));
+    assert_eq!(evaluated.load(Ordering::Relaxed), 0);
+    assert_eq!(FORMATTED.load(Ordering::Relaxed), 0);
+    assert_eq!(LOGGED.load(Ordering::Relaxed), 0);
+    info!({ value = ?expensive(&evaluated) }, "logged: {:?}", expensive(&evaluated));
+    warning!("logged: {:?}", expensive(&evaluated));
+    assert_eq!(evaluated.load(Ordering::Relaxed), 3);
+    assert_eq!(FORMATTED.load(Ordering::Relaxed), 3);
+    assert_eq!(LOGGED.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn interest() {
+    install();
+    for _ in 0..10 {
+        log!(Level::Trace, "cached");
+    }
+    assert_eq!(CHECKED.load(Ordering::Relaxed), 1);
+    bp3d_debug::logger::rebuild_interest();
+    for _ in 0..10 {
+        log!(Level::Trace, "cached");
+    }
+    assert_eq!(CHECKED.load(Ordering::Relaxed), 2);
+}
@@ -0,0 +1,101 @@
+// Copyright (c) 2025, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+mod common;
+
+use bp3d_debug::span;
+use bp3d_debug::trace::Trace;
+use common::{capture, Event};
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+
+/// A future which returns pending a given number of times before completing.
+struct Yield(usize);
+
+impl Future for Yield {
+    type Output = usize;
+
+    fn poll(mut self: std::pin::Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.0 == 0 {
+            return Poll::Ready(42);
+        }
+        self.0 -= 1;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+#[test]
+fn enter_per_poll() {
+    let capture = capture();
+    let span = span!(TRACED_FUTURE);
+    let mut future = pin!(Yield(2).trace(span));
+    let mut cx = Context::from_waker(Waker::noop());
+    assert!(future.as_mut().poll(&mut cx).is_pending());
+    assert!(future.as_mut().poll(&mut cx).is_pending());
+    assert_eq!(future.as_mut().poll(&mut cx), Poll::Ready(42));
+    let events = capture.events();
+    let id = events
+        .iter()
+        .find_map(|v| match v {
+            Event::SpanCreate(id, "TRACED_FUTURE", _) => Some(*id),
+            _ => None,
+        })
+        .unwrap();
+    let events: Vec<_> = events
+        .into_iter()
+        .filter(|v| match v {
+            Event::SpanEnter(v) | Event::SpanExit(v) | Event::SpanDestroy(v) => *v == id,
+            _ => false,
+        })
+        .collect();
+    assert_eq!(
+        events,
+        [
+            Event::SpanEnter(id),
+            Event::SpanExit(id),
+            Event::SpanEnter(id),
+            Event::SpanExit(id),
+            Event::SpanEnter(id),
+            Event::SpanExit(id),
+            Event::SpanDestroy(id)
+        ]
+    );
+}
+
+#[test]
+fn not_unpin() {
+    capture();
+    let span = span!(TRACED_ASYNC);
+    // Async blocks are not Unpin, so the inner future must stay pinned.
+    let mut future = pin!(async { Yield(1).await + 1 }.trace(span));
+    let mut cx = Context::from_waker(Waker::noop());
+    assert!(future.as_mut().poll(&mut cx).is_pending());
+    assert_eq!(future.as_mut().poll(&mut cx), Poll::Ready(43));
+}
@@ -0,0 +1,180 @@
+// Copyright (c) 2025, BlockProject 3D
+//
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of BlockProject 3D nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS
+// "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT
+// LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR
+// A PARTICULAR PURPOSE ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR
+// CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL,
+// EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO,
+// PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR
+// PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF
+// LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING
+// NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+#![cfg(feature = "tracing-compat")]
+
+mod common;
+
+use bp3d_debug::logger::Level;
+use bp3d_debug::tracing_compat::TracingBridge;
+use common::{capture, Event};
+
+#[test]
+fn round_trip() {
+    let capture = capture();
+    tracing::subscriber::with_default(TracingBridge::new(), || {
+        let span = tracing::info_span!("load", path = "a.bin", size = 42u64);
+        let span2 = span.clone();
+        {
+            let _entered = span.enter();
+            span.record("size", 43u64);
+            tracing::info!(count = -3, ok = true, point = ?(1, 2), "loaded {}", "a.bin");
+        }
+        drop(span);
+        drop(span2);
+    });
+    let events = capture.events();
+    let id = events
+        .iter()
+        .find_map(|v| match v {
+            Event::SpanCreate(id, "load", fields) => {
+                assert_eq!(
+                    *fields,
+                    [
+                        ("path".into(), "a.bin".into()),
+                        ("size".into(), "42".into())
+                    ]
+                );
+                Some(*id)
+            }
+            _ => None,
+        })
+        .unwrap();
+    let log = events
+        .iter()
+        .find_map(|v| match v {
+            Event::Log(level, module, _, _, msg, fields) if msg == "loaded a.bin" => {
+                Some((*level, *module, fields.clone()))
+            }
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(log.0, Level::Info);
+    assert_eq!(log.1, "tracing");
+    assert_eq!(
+        log.2,
+        [
+            ("count".into(), "-3".into()),
+            ("ok".into(), "true".into()),
+            ("point".into(), "(1, 2)".into())
+        ]
+    );
+    let span_events: Vec<&Event> = events
+        .iter()
+        .filter(|v| match v {
+            Event::SpanEnter(v)
+            | Event::SpanExit(v)
+            | Event::SpanDestroy(v)
+            | Event::SpanRecord(v, _) => *v == id,
+            _ => false,
+        })
+        .collect();
+    assert_eq!(
+        span_events,
+        [
+            &Event::SpanEnter(id),
+            &Event::SpanRecord(id, vec![("size".into(), "43".into())]),
+            &Event::SpanExit(id),
+            &Event::SpanDestroy(id)
+        ]
+    );
+}
+
+#[test]
+fn filtered() {
+    let capture = capture();
+    tracing::subscriber::with_default(TracingBridge::new(), || {
+        let span = tracing::trace_span!("filtered_span");
+        assert!(span.is_disabled());
+        tracing::trace!("filtered event");
+        let span = tracing::debug_span!("kept_span");
+        assert_eq!(span.id().unwrap().into_u64(), 1);
+        tracing::debug!("kept event");
+    });
+    let events = capture.events();
+    assert!(!events.iter().any(|v| match v {
+        Event::SpanCreate(_, name, _) => *name == "filtered_span",
+        Event::Log(_, _, _, _, msg, _) => msg == "filtered event",
+        _ => false,
+    }));
+    assert!(events
+        .iter()
+        .any(|v| matches!(v, Event::SpanCreate(_, "kept_span", _))));
+    assert!(events
+        .iter()
+        .any(|v| matches!(v, Event::Log(Level::Debug, _, _, _, msg, _) if msg == "kept event")));
+}
+
+#[test]
+fn parent() {
+    let capture = capture();
+    tracing::subscriber::with_default(TracingBridge::new(), || {
+        let root = tracing::info_span!("parent_root");
+        let _root = root.enter();
+        let _contextual = tracing::info_span!("parent_contextual");
+        let _explicit = tracing::info_span!(parent: None, "parent_none");
+        // Spans entered through tracing are visible to the native span stack.
+        let _native = bp3d_debug::span!(PARENT_NATIVE);
+    });
+    let events = capture.events();
+    let id = |name| {
+        events
+            .iter()
+            .find_map(|v| match v {
+                Event::SpanCreate(id, v, _) if *v == name => Some(*id),
+                _ => None,
+            })
+            .unwrap()
+    };
+    let root = id("parent_root");
+    assert!(events.contains(&Event::SpanParent(id("parent_contextual"), root)));
+    assert!(events.contains(&Event::SpanParent(id("PARENT_NATIVE"), root)));
+    assert!(!events
+        .iter()
+        .any(|v| matches!(v, Event::SpanParent(v, _) if *v == id("parent_none"))));
+}
+
+#[test]
+fn shared_callsites() {
+    let capture = capture();
+    for _ in 0..2 {
+        tracing::subscriber::with_default(TracingBridge::new(), || {
+            drop(tracing::info_span!("shared_span"));
+        });
+    }
+    let callsites: Vec<u64> = capture
+        .events()
+        .iter()
+        .filter_map(|v| match v {
+            Event::SpanCreate(id, "shared_span", _) => Some(id.into_raw().get() >> 32),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(callsites.len(), 2);
+    assert_eq!(callsites[0], callsites[1]);
+}